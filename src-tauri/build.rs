@@ -1,3 +1,34 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
 fn main() {
+    // 构建环境没有 git 或不在仓库中时不设置该变量，运行时按未知处理
+    if let Some(hash) = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|hash| !hash.is_empty())
+    {
+        println!("cargo:rustc-env=GIT_COMMIT_HASH={hash}");
+    }
+    // HEAD 通常只记录分支名，同一分支上的新提交只会改动分支的 ref 文件或 packed-refs；
+    // 监视不存在的文件会让构建脚本每次都重新执行，因此只监视存在的文件
+    let mut watched = vec![
+        "../.git/HEAD".to_string(),
+        "../.git/packed-refs".to_string(),
+    ];
+    if let Some(reference) = fs::read_to_string("../.git/HEAD")
+        .ok()
+        .and_then(|head| Some(head.strip_prefix("ref:")?.trim().to_string()))
+    {
+        watched.push(format!("../.git/{reference}"));
+    }
+    for path in watched.iter().filter(|path| Path::new(path).exists()) {
+        println!("cargo:rerun-if-changed={path}");
+    }
+
     tauri_build::build()
 }
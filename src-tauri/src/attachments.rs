@@ -0,0 +1,274 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{async_runtime, AppHandle, DragDropEvent, Emitter, Manager, Window, WindowEvent};
+use tracing::{info, warn};
+
+use crate::settings;
+
+/// 拖入文件处理完成后发送给接收拖放的窗口，载荷为 [`FilesDropped`]
+pub(crate) const FILE_DROPPED_EVENT: &str = "import://file-dropped";
+/// 应用数据目录下保存导入附件的子目录
+const ATTACHMENTS_DIR_NAME: &str = "attachments";
+/// 超过该大小的文件不导入
+const MAX_ATTACHMENT_BYTES: u64 = 200 * 1024 * 1024;
+/// 这些扩展名的文件还要检查文件头
+const MAGIC_BYTES: &[(&str, &[u8])] = &[("pdf", b"%PDF")];
+/// 临时文件序号，同时处理多次拖放时互不覆盖
+static NEXT_TEMP_ID: AtomicU64 = AtomicU64::new(0);
+
+/// 已导入的文件；同一内容只保存一份，`path` 为 `attachments/<sha256>.<扩展名>`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedFile {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub hash: String,
+}
+
+/// 未通过校验或复制失败的文件
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+/// `import://file-dropped` 事件载荷，一次拖放的所有文件
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FilesDropped {
+    pub imported: Vec<ImportedFile>,
+    pub rejected: Vec<RejectedFile>,
+}
+
+/// 设置中的扩展名不区分大小写，可以带 `.`
+fn extension_allowed(extension: &str, allowed: &[String]) -> bool {
+    allowed.iter().any(|allowed| {
+        allowed
+            .trim_start_matches('.')
+            .eq_ignore_ascii_case(extension)
+    })
+}
+
+/// Windows 上的 UNC 路径（`\\server\share`）指向网络共享，读取可能长时间卡住，不导入；
+/// `\\?\` 与 `\\.\` 开头的本地设备路径除外
+fn is_network_path(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    (path.starts_with(r"\\") && !path.starts_with(r"\\?\") && !path.starts_with(r"\\.\"))
+        || path.starts_with(r"\\?\UNC\")
+}
+
+/// 边复制到临时文件边计算 SHA-256
+fn copy_hashed(source: &mut File, target: &Path) -> io::Result<String> {
+    let mut output = File::create(target)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = source.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        output.write_all(&buffer[..read])?;
+    }
+    output.sync_all()?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn import_file(dir: &Path, path: &Path, allowed: &[String]) -> Result<ImportedFile, String> {
+    if is_network_path(path) {
+        return Err("网络路径上的文件请先复制到本地".to_string());
+    }
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !extension_allowed(&extension, allowed) {
+        return Err(format!("不支持的文件类型，仅支持 {}", allowed.join("、")));
+    }
+    let metadata = fs::metadata(path).map_err(|e| e.to_string())?;
+    if !metadata.is_file() {
+        return Err("不是普通文件".to_string());
+    }
+    if metadata.len() > MAX_ATTACHMENT_BYTES {
+        return Err(format!(
+            "文件过大（{} MB），上限为 {} MB",
+            metadata.len() / 1024 / 1024,
+            MAX_ATTACHMENT_BYTES / 1024 / 1024
+        ));
+    }
+
+    let mut source = File::open(path).map_err(|e| e.to_string())?;
+    if let Some((_, magic)) = MAGIC_BYTES.iter().find(|(ext, _)| *ext == extension) {
+        let mut header = vec![0; magic.len()];
+        if source.read_exact(&mut header).is_err() || header != *magic {
+            return Err(format!("文件内容不是 {}", extension.to_ascii_uppercase()));
+        }
+        source = File::open(path).map_err(|e| e.to_string())?;
+    }
+
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let temp_id = NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed);
+    let temp = dir.join(format!(".import-{}-{temp_id}.tmp", std::process::id()));
+    let hash = match copy_hashed(&mut source, &temp) {
+        Ok(hash) => hash,
+        Err(error) => {
+            let _ = fs::remove_file(&temp);
+            return Err(error.to_string());
+        }
+    };
+    let target = dir.join(format!("{hash}.{extension}"));
+    if target.is_file() {
+        // 内容相同的文件已导入过
+        let _ = fs::remove_file(&temp);
+    } else if let Err(error) = fs::rename(&temp, &target) {
+        let _ = fs::remove_file(&temp);
+        return Err(error.to_string());
+    }
+    Ok(ImportedFile {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path: target.to_string_lossy().into_owned(),
+        size: metadata.len(),
+        hash,
+    })
+}
+
+/// 逐个校验并导入拖入的文件
+fn import_all(app: &AppHandle, paths: &[PathBuf]) -> FilesDropped {
+    let mut dropped = FilesDropped::default();
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(ATTACHMENTS_DIR_NAME),
+        Err(error) => {
+            dropped.rejected = paths
+                .iter()
+                .map(|path| RejectedFile {
+                    path: path.to_string_lossy().into_owned(),
+                    reason: format!("无法确定附件目录: {error}"),
+                })
+                .collect();
+            return dropped;
+        }
+    };
+    let allowed = settings::load(app).import_extensions;
+    for path in paths {
+        match import_file(&dir, path, &allowed) {
+            Ok(file) => dropped.imported.push(file),
+            Err(reason) => {
+                warn!(path = %path.display(), %reason, "拖入的文件未导入");
+                dropped.rejected.push(RejectedFile {
+                    path: path.to_string_lossy().into_owned(),
+                    reason,
+                });
+            }
+        }
+    }
+    dropped
+}
+
+/// 文件拖放到窗口上时在阻塞线程池中校验并导入，完成后向该窗口发送一次 `import://file-dropped`
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event else {
+        return;
+    };
+    if paths.is_empty() {
+        return;
+    }
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    let paths = paths.clone();
+    async_runtime::spawn_blocking(move || {
+        let dropped = import_all(&app, &paths);
+        info!(
+            imported = dropped.imported.len(),
+            rejected = dropped.rejected.len(),
+            "处理拖入的文件"
+        );
+        let _ = app.emit_to(label.as_str(), FILE_DROPPED_EVENT, dropped);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 本测试独用的临时目录，`source` 放拖入的文件，`attachments` 为导入目标
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "openreview-attachments-{}-{name}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("source")).unwrap();
+        dir
+    }
+
+    fn pdf_allowed() -> Vec<String> {
+        vec![".PDF".to_string()]
+    }
+
+    #[test]
+    fn names_with_spaces_unicode_and_commas_are_imported() {
+        let dir = scratch("names");
+        let target = dir.join(ATTACHMENTS_DIR_NAME);
+        for (index, name) in ["my report.pdf", "论文 草稿（终版）.pdf", "a,b, c.pdf"]
+            .into_iter()
+            .enumerate()
+        {
+            let source = dir.join("source").join(name);
+            fs::write(&source, format!("%PDF-1.7 {index}")).unwrap();
+            let file = import_file(&target, &source, &pdf_allowed()).unwrap();
+            assert_eq!(file.name, name);
+            assert_eq!(
+                file.path,
+                target.join(format!("{}.pdf", file.hash)).to_string_lossy()
+            );
+            assert_eq!(fs::read(&file.path).unwrap(), fs::read(&source).unwrap());
+            assert_eq!(file.size, fs::metadata(&source).unwrap().len());
+        }
+        // 只留下导入的文件，临时文件都已清理
+        assert_eq!(fs::read_dir(&target).unwrap().count(), 3);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn same_content_is_stored_once() {
+        let dir = scratch("dedup");
+        let target = dir.join(ATTACHMENTS_DIR_NAME);
+        let first = dir.join("source").join("第一份, 副本.pdf");
+        let second = dir.join("source").join("second copy.pdf");
+        fs::write(&first, "%PDF same").unwrap();
+        fs::write(&second, "%PDF same").unwrap();
+        let first = import_file(&target, &first, &pdf_allowed()).unwrap();
+        let second = import_file(&target, &second, &pdf_allowed()).unwrap();
+        assert_eq!(first.path, second.path);
+        assert_ne!(first.name, second.name);
+        assert_eq!(fs::read_dir(&target).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn wrong_type_or_content_is_rejected() {
+        let dir = scratch("rejected");
+        let target = dir.join(ATTACHMENTS_DIR_NAME);
+        let text = dir.join("source").join("notes, draft.txt");
+        let fake = dir.join("source").join("假的 文件.pdf");
+        fs::write(&text, "hello").unwrap();
+        fs::write(&fake, "not a pdf").unwrap();
+        assert!(import_file(&target, &text, &pdf_allowed())
+            .unwrap_err()
+            .starts_with("不支持的文件类型"));
+        assert_eq!(
+            import_file(&target, &fake, &pdf_allowed()).unwrap_err(),
+            "文件内容不是 PDF"
+        );
+        assert!(import_file(&target, &dir.join("source"), &["".to_string()]).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,283 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+
+use crate::ports::PortError;
+
+/// 自启动时附加的参数：托盘可用时只显示托盘图标，不显示主窗口
+#[cfg(desktop)]
+pub(crate) const HIDDEN_ARG: &str = "--hidden";
+
+#[cfg_attr(
+    not(any(windows, target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+)]
+fn failed(error: impl ToString) -> PortError {
+    PortError::AutostartFailed {
+        reason: error.to_string(),
+    }
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn unavailable(reason: impl Into<String>) -> PortError {
+    PortError::AutostartUnavailable {
+        reason: reason.into(),
+    }
+}
+
+/// 自启动时执行的程序；AppImage 运行时 `current_exe` 是临时挂载点，改用 AppImage 文件本身
+#[cfg_attr(
+    not(any(windows, target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+)]
+fn executable() -> Result<PathBuf, PortError> {
+    #[cfg(target_os = "linux")]
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().map_err(failed)
+}
+
+#[cfg(windows)]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+/// 任务管理器“启动”页禁用的项记录在这里，首字节为奇数表示已禁用
+#[cfg(windows)]
+const STARTUP_APPROVED_KEY: &str =
+    r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved\Run";
+
+/// `reg query` 找到该值时返回其输出，不存在时返回 `None`
+#[cfg(windows)]
+fn query_value(key: &str, name: &str) -> Result<Option<String>, PortError> {
+    let output = crate::ports::run_tool("reg", &["query", key, "/v", name])?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+#[cfg(windows)]
+fn delete_value(key: &str, name: &str) -> Result<(), PortError> {
+    if query_value(key, name)?.is_none() {
+        return Ok(());
+    }
+    let output = crate::ports::run_tool("reg", &["delete", key, "/v", name, "/f"])?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(failed(String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(windows)]
+pub(crate) fn is_enabled(app: &AppHandle) -> Result<bool, PortError> {
+    let name = &app.config().identifier;
+    if query_value(RUN_KEY, name)?.is_none() {
+        return Ok(false);
+    }
+    // 形如 `    <name>    REG_BINARY    030000000000000000000000`
+    let disabled = query_value(STARTUP_APPROVED_KEY, name)?
+        .and_then(|output| {
+            let data = output
+                .split_once("REG_BINARY")?
+                .1
+                .trim()
+                .get(..2)?
+                .to_string();
+            u8::from_str_radix(&data, 16).ok()
+        })
+        .is_some_and(|flag| flag % 2 == 1);
+    Ok(!disabled)
+}
+
+#[cfg(windows)]
+pub(crate) fn set_enabled(app: &AppHandle, enabled: bool, hidden: bool) -> Result<(), PortError> {
+    let name = app.config().identifier.clone();
+    // 无论开启还是关闭都清掉任务管理器中的禁用标记，避免重新开启后仍不生效
+    delete_value(STARTUP_APPROVED_KEY, &name)?;
+    if !enabled {
+        return delete_value(RUN_KEY, &name);
+    }
+    let mut command = format!("\"{}\"", executable()?.display());
+    if hidden {
+        command.push(' ');
+        command.push_str(HIDDEN_ARG);
+    }
+    let args = [
+        "add",
+        RUN_KEY,
+        "/v",
+        name.as_str(),
+        "/t",
+        "REG_SZ",
+        "/d",
+        command.as_str(),
+        "/f",
+    ];
+    let output = crate::ports::run_tool("reg", &args)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(failed(String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn launch_agent_path(app: &AppHandle) -> Result<PathBuf, PortError> {
+    // App Sandbox 中的程序不能注册 LaunchAgent
+    if std::env::var_os("APP_SANDBOX_CONTAINER_ID").is_some() {
+        return Err(unavailable("应用运行在 App Sandbox 中，无法注册登录项"));
+    }
+    let home = app
+        .path()
+        .home_dir()
+        .map_err(|_| unavailable("无法确定用户主目录"))?;
+    let file_name = format!("{}.plist", app.config().identifier);
+    Ok(home.join("Library").join("LaunchAgents").join(file_name))
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn is_enabled(app: &AppHandle) -> Result<bool, PortError> {
+    Ok(launch_agent_path(app)?.is_file())
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn set_enabled(app: &AppHandle, enabled: bool, hidden: bool) -> Result<(), PortError> {
+    let path = launch_agent_path(app)?;
+    if !enabled {
+        return remove_file(&path);
+    }
+    let mut arguments = format!(
+        "<string>{}</string>",
+        xml_escape(&executable()?.to_string_lossy())
+    );
+    if hidden {
+        arguments.push_str(&format!("<string>{HIDDEN_ARG}</string>"));
+    }
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>{arguments}</array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        label = xml_escape(&app.config().identifier),
+    );
+    write_file(&path, &plist)
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_entry_path(app: &AppHandle) -> Result<PathBuf, PortError> {
+    // Flatpak / Snap 沙盒中写入的文件对宿主的会话管理器不可见
+    if std::env::var_os("FLATPAK_ID").is_some() || std::env::var_os("SNAP").is_some() {
+        return Err(unavailable(
+            "应用运行在 Flatpak / Snap 沙盒中，无法写入自启动项",
+        ));
+    }
+    let config = app
+        .path()
+        .config_dir()
+        .map_err(|_| unavailable("无法确定用户配置目录"))?;
+    let file_name = format!("{}.desktop", app.config().identifier);
+    Ok(config.join("autostart").join(file_name))
+}
+
+/// 按 Desktop Entry 规范为 `Exec` 中的参数加引号
+#[cfg(target_os = "linux")]
+fn desktop_quote(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    if !arg.contains(|c: char| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c)) {
+        return arg;
+    }
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// 文件存在且未被 `Hidden=true` 或 `X-GNOME-Autostart-enabled=false` 禁用时视为已开启
+#[cfg(target_os = "linux")]
+pub(crate) fn is_enabled(app: &AppHandle) -> Result<bool, PortError> {
+    let path = desktop_entry_path(app)?;
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(error) => return Err(failed(error)),
+    };
+    let disabled = content.lines().any(|line| {
+        matches!(
+            line.trim().replace(' ', "").as_str(),
+            "Hidden=true" | "X-GNOME-Autostart-enabled=false"
+        )
+    });
+    Ok(!disabled)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn set_enabled(app: &AppHandle, enabled: bool, hidden: bool) -> Result<(), PortError> {
+    let path = desktop_entry_path(app)?;
+    if !enabled {
+        return remove_file(&path);
+    }
+    let mut exec = desktop_quote(&executable()?.to_string_lossy());
+    if hidden {
+        exec.push(' ');
+        exec.push_str(HIDDEN_ARG);
+    }
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName={name}\nExec={exec}\nX-GNOME-Autostart-enabled=true\n",
+        name = app.package_info().name,
+    );
+    write_file(&path, &entry)
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn write_file(path: &std::path::Path, content: &str) -> Result<(), PortError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(failed)?;
+    }
+    std::fs::write(path, content).map_err(failed)
+}
+
+/// 删除自启动项，不存在时视为成功
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn remove_file(path: &std::path::Path) -> Result<(), PortError> {
+    match std::fs::remove_file(path) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(failed(error)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub(crate) fn is_enabled(_app: &AppHandle) -> Result<bool, PortError> {
+    Err(unavailable("当前平台不支持开机自启动"))
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub(crate) fn set_enabled(
+    _app: &AppHandle,
+    _enabled: bool,
+    _hidden: bool,
+) -> Result<(), PortError> {
+    Err(unavailable("当前平台不支持开机自启动"))
+}
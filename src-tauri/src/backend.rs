@@ -0,0 +1,1852 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::{self, Receiver};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::backend_log::{begin_session, now_millis, record, BackendLog, LogStream};
+use crate::binary::validate_backend_binary;
+use crate::conflict::CONFLICT_SEARCH_SPAN;
+use crate::health::probe_ready;
+use crate::metrics::{sample_backend, DEFAULT_METRICS_INTERVAL};
+use crate::orphan::{remove_pid_file, write_pid_file};
+use crate::ports::{
+    accepts_connections, can_bind, find_free_port_excluding, validate_port, wait_for_state,
+    PortError, PortState, Protocol, StartupFailure, COMMON_DEV_PORTS, WAIT_POLL_INTERVAL,
+};
+use crate::process::{
+    kill_port_listeners, kill_tree, process_alive, terminate, wait_for_exit, KillOptions,
+};
+use crate::run_blocking;
+use crate::settings::{self, Settings};
+
+/// 打包在应用内的后端可执行文件名（不含平台后缀）
+pub(crate) const BACKEND_SIDECAR: &str = "openreview-server";
+/// 命令未指定 `workspaceId` 时使用的工作区，兼容只运行一个后端的调用方
+pub(crate) const DEFAULT_WORKSPACE: &str = "default";
+const MAX_WORKSPACE_ID_LEN: usize = 64;
+/// 应用数据目录下存放各工作区默认数据目录的子目录
+const WORKSPACES_DIR_NAME: &str = "workspaces";
+/// 设置了代理时注入后端的环境变量
+const PROXY_ENV_KEYS: [&str; 2] = ["HTTP_PROXY", "HTTPS_PROXY"];
+/// 后端进程退出时发送的事件，载荷为 [`BackendExit`]
+pub(crate) const BACKEND_EXITED_EVENT: &str = "backend-exited";
+/// `restart_backend` 开始时发送，载荷为工作区 ID
+pub(crate) const BACKEND_RESTARTING_EVENT: &str = "backend://restarting";
+/// `restart_backend` 启动的新进程开始接受连接时发送，载荷为 [`BackendHandle`]
+pub(crate) const BACKEND_READY_EVENT: &str = "backend://ready";
+/// 后端意外退出时发送，载荷为 [`BackendCrash`]
+pub(crate) const BACKEND_CRASHED_EVENT: &str = "backend://crashed";
+/// 自动重启成功拉起新进程时发送，载荷为 [`BackendRestarted`]
+pub(crate) const BACKEND_RESTARTED_EVENT: &str = "backend-restarted";
+/// 自动重启次数用尽时发送，载荷为 [`BackendGaveUp`]
+pub(crate) const BACKEND_GAVE_UP_EVENT: &str = "backend-gave-up";
+/// 后端状态每次变化时发送，载荷为 [`BackendStatus`]
+pub(crate) const BACKEND_STATUS_EVENT: &str = "backend://status-changed";
+/// `start_backend_and_wait` 每完成一个启动阶段发送一次，载荷为 [`StartupProgress`]
+pub(crate) const BACKEND_STARTUP_EVENT: &str = "backend://startup";
+/// 强制结束后确认进程退出的最长等待时间
+const FORCED_EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+/// 应用退出前正常停止后端的最长等待时间，超时后强制结束
+const EXIT_STOP_TIMEOUT: Duration = Duration::from_secs(3);
+/// 启动后等待新进程开始监听的最长时间
+pub(crate) const BACKEND_READY_TIMEOUT: Duration = Duration::from_secs(30);
+/// 启动等待期间单次健康检查的超时时间
+const STARTUP_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+/// `backend://crashed` 事件附带的 stderr 行数
+const STDERR_TAIL_LINES: usize = 50;
+/// 未在配置中指定时的自动重启次数上限
+const DEFAULT_MAX_RETRIES: u32 = 5;
+/// 未在配置中指定时自动重启的初始退避时间（毫秒），每次失败翻倍
+const DEFAULT_BACKOFF_MS: u64 = 1000;
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// 运行超过该时长后才崩溃视为偶发故障，重新开始计算重启次数
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+/// 名称以这些后缀结尾的环境变量在状态与日志中隐藏取值
+const SECRET_ENV_SUFFIXES: [&str; 2] = ["_KEY", "_TOKEN"];
+const REDACTED: &str = "******";
+
+/// `backend-exited` 事件载荷；正常退出时 `code` 为退出码，被信号结束时 `signal` 为信号值（仅 Unix）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendExit {
+    pub workspace_id: String,
+    pub pid: u32,
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+}
+
+/// `backend://crashed` 事件载荷，`stderr` 为退出前的最后若干行输出
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendCrash {
+    pub workspace_id: String,
+    pub pid: u32,
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    pub stderr: Vec<String>,
+}
+
+/// `backend-restarted` 事件载荷，`attempt` 为本轮自动重启的序号，从 1 开始
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendRestarted {
+    pub workspace_id: String,
+    pub attempt: u32,
+    pub pid: u32,
+    pub port: u16,
+}
+
+/// `backend-gave-up` 事件载荷，`attempts` 为已尝试的次数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendGaveUp {
+    pub workspace_id: String,
+    pub attempts: u32,
+}
+
+/// `start_backend_and_wait` 的启动阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartupStage {
+    /// 进程已启动
+    Spawned,
+    /// 端口开始接受连接
+    PortOpen,
+    /// 健康检查通过，可以开始发送请求
+    Healthy,
+}
+
+/// `backend://startup` 事件载荷
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupProgress {
+    pub workspace_id: String,
+    pub stage: StartupStage,
+    pub pid: u32,
+    pub port: u16,
+}
+
+/// 后端启动参数
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendConfig {
+    /// 未指定时优先使用设置中的 `backendPort`，被占用时在其后 100 个端口内分配一个空闲端口（跳过其他工作区正在使用的端口），
+    /// 自动重启与 `restart_backend` 沿用该端口
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// 以 `--data-dir` 传给后端。未指定时默认工作区使用设置中的 `dataDir`（未设置则由后端使用自身默认目录），
+    /// 其他工作区使用应用数据目录下的 `workspaces/<ID>`，彼此隔离
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+    /// 追加在内置参数之后的额外参数
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 意外退出后的自动重启策略，缺省为最多 5 次、初始等待 1 秒
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    /// 额外的环境变量，与设置中的 `backendEnv` 合并，同名时以此处为准
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// 用户自行构建的后端程序，未指定时使用打包的 sidecar；启动前按 `validate_backend_path` 的规则校验
+    #[serde(default)]
+    pub executable: Option<PathBuf>,
+    /// 资源采样间隔，缺省为 5 秒，最小 1 秒
+    #[serde(default)]
+    pub metrics_interval_ms: Option<u64>,
+}
+
+/// 后端以非零状态退出或被信号结束后的自动重启策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RestartPolicy {
+    /// 自上次稳定运行或手动启动以来的自动重启次数上限，为 0 时不自动重启
+    pub max_retries: u32,
+    /// 第一次重启前的等待时间，此后每次翻倍，不超过 30 秒
+    pub backoff_ms: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff_ms: DEFAULT_BACKOFF_MS,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// 已重启 `attempts` 次后再次崩溃时的下一次重启：序号与等待时间，次数用尽时返回 `None`
+    fn next_attempt(&self, attempts: u32) -> Option<(u32, Duration)> {
+        (attempts < self.max_retries).then(|| {
+            let attempt = attempts + 1;
+            let base = Duration::from_millis(self.backoff_ms);
+            (attempt, restart_backoff(base, attempt))
+        })
+    }
+}
+
+/// 正在运行的后端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendHandle {
+    pub workspace_id: String,
+    pub pid: u32,
+    pub port: u16,
+}
+
+/// 最近一次自动重启的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartAttempt {
+    /// 从 1 开始的序号
+    pub attempt: u32,
+    /// 启动成功时为新进程的 PID
+    pub pid: Option<u32>,
+    pub error: Option<String>,
+}
+
+/// 后端生命周期；只由启动、停止与退出监控代码切换
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackendPhase {
+    #[default]
+    Stopped,
+    /// 进程已启动，端口尚未开始接受连接
+    Starting,
+    Running,
+    /// 意外退出，可能正在等待自动重启
+    Crashed,
+    Stopping,
+}
+
+/// `get_backend_status` 的返回值与 `backend://status-changed` 事件载荷；
+/// `started_at` 为 Unix 毫秒时间戳，崩溃后仍保留最后一个进程的 `pid` / `port`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendStatus {
+    pub workspace_id: String,
+    pub state: BackendPhase,
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    pub started_at: Option<u64>,
+    /// 自上次稳定运行或手动启动以来的自动重启次数
+    pub restart_count: u32,
+    pub last_exit_code: Option<i32>,
+    pub auto_restart: bool,
+    pub last_restart: Option<RestartAttempt>,
+    /// 当前进程实际使用的环境变量，敏感取值已隐藏
+    pub env: BTreeMap<String, String>,
+    /// 保存的环境变量在进程启动后被修改过，需要重启才能生效
+    pub restart_required: bool,
+}
+
+/// 后端子进程的句柄；结束进程后调用 `kill` 释放 shell 插件持有的资源
+trait BackendChild: Send {
+    fn kill(self: Box<Self>);
+}
+
+impl BackendChild for CommandChild {
+    fn kill(self: Box<Self>) {
+        let _ = CommandChild::kill(*self);
+    }
+}
+
+struct BackendProcess {
+    child: Box<dyn BackendChild>,
+    handle: BackendHandle,
+    started: Instant,
+}
+
+struct BackendSlot {
+    process: Option<BackendProcess>,
+    /// 最近一次成功启动时的配置，供 `restart_backend` 复用
+    config: Option<BackendConfig>,
+    phase: BackendPhase,
+    /// 最近一个进程，停止期间与崩溃后 `process` 已被取走时仍用于展示
+    last_handle: Option<BackendHandle>,
+    started_at: Option<u64>,
+    last_exit_code: Option<i32>,
+    auto_restart: bool,
+    restart_attempts: u32,
+    last_restart: Option<RestartAttempt>,
+    /// 已隐藏敏感取值的实际环境变量
+    env: BTreeMap<String, String>,
+    restart_required: bool,
+    /// 正在启动（创建日志文件与子进程期间不持锁）的端口，期间视为已被占用
+    launching: Option<u16>,
+}
+
+impl Default for BackendSlot {
+    fn default() -> Self {
+        Self {
+            process: None,
+            config: None,
+            phase: BackendPhase::Stopped,
+            last_handle: None,
+            started_at: None,
+            last_exit_code: None,
+            auto_restart: true,
+            restart_attempts: 0,
+            last_restart: None,
+            env: BTreeMap::new(),
+            restart_required: false,
+            launching: None,
+        }
+    }
+}
+
+impl BackendSlot {
+    fn status(&self, workspace: &str) -> BackendStatus {
+        BackendStatus {
+            workspace_id: workspace.to_string(),
+            state: self.phase,
+            pid: self.last_handle.as_ref().map(|handle| handle.pid),
+            port: self.last_handle.as_ref().map(|handle| handle.port),
+            started_at: self.started_at,
+            restart_count: self.restart_attempts,
+            last_exit_code: self.last_exit_code,
+            auto_restart: self.auto_restart,
+            last_restart: self.last_restart.clone(),
+            env: self.env.clone(),
+            restart_required: self.restart_required,
+        }
+    }
+}
+
+/// 各工作区的后端，以工作区 ID 为键；停止后保留配置供重启使用
+type Slots = HashMap<String, BackendSlot>;
+
+fn slot_mut<'a>(slots: &'a mut Slots, workspace: &str) -> &'a mut BackendSlot {
+    slots.entry(workspace.to_string()).or_default()
+}
+
+/// 切换 `workspace` 的生命周期并发送 `backend://status-changed`；接管锁，在释放后才发送事件
+fn transition(
+    host: &impl BackendHost,
+    mut slots: MutexGuard<'_, Slots>,
+    workspace: &str,
+    phase: BackendPhase,
+) {
+    let slot = slot_mut(&mut slots, workspace);
+    if phase == BackendPhase::Stopped {
+        slot.last_handle = None;
+        slot.started_at = None;
+        slot.env.clear();
+        slot.restart_required = false;
+    }
+    slot.phase = phase;
+    let status = slot.status(workspace);
+    drop(slots);
+    host.emit_event(BACKEND_STATUS_EVENT, status);
+}
+
+/// 由 Rust 侧启动的后端子进程（每个工作区一个），通过 `.manage()` 注册为全局状态
+#[derive(Default)]
+pub(crate) struct BackendState {
+    slots: Mutex<Slots>,
+    /// 已开始退出或关闭主窗口前的停止流程，再次收到请求时直接放行
+    exiting: AtomicBool,
+}
+
+impl BackendState {
+    fn lock(&self) -> MutexGuard<'_, Slots> {
+        // 持锁期间不会 panic，锁中毒时数据仍然可用
+        self.slots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 停止失败时放回句柄并恢复之前的状态，期间已有新后端启动则保留新的
+    fn restore(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        process: BackendProcess,
+        phase: BackendPhase,
+    ) {
+        let mut slots = self.lock();
+        let slot = slot_mut(&mut slots, workspace);
+        if slot.process.is_none() {
+            slot.process = Some(process);
+            transition(app, slots, workspace, phase);
+        }
+    }
+}
+
+/// 监控与自动重启后端时用到的应用能力。应用中由 [`AppHandle`] 实现；
+/// 测试中用记录事件、以普通子进程代替后端的实现代替，不需要 Tauri 运行时
+pub(crate) trait BackendHost: Clone + Send + Sync + 'static {
+    fn backends(&self) -> &BackendState;
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S);
+    /// 记录一段输出，返回拆分后的行，见 [`record`]
+    fn record_output(&self, workspace: &str, stream: LogStream, bytes: &[u8]) -> Vec<String>;
+    /// 后端已退出，删除其 PID 文件
+    fn forget_pid(&self, workspace: &str, pid: u32);
+    /// 崩溃后重新启动 `workspace` 的后端，不重置自动重启次数，见 [`launch`]
+    fn relaunch(&self, workspace: &str, config: &BackendConfig)
+        -> Result<BackendHandle, PortError>;
+}
+
+impl BackendHost for AppHandle {
+    fn backends(&self) -> &BackendState {
+        self.state::<BackendState>().inner()
+    }
+
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        let _ = self.emit(event, payload);
+    }
+
+    fn record_output(&self, workspace: &str, stream: LogStream, bytes: &[u8]) -> Vec<String> {
+        record(self, workspace, stream, bytes)
+    }
+
+    fn forget_pid(&self, workspace: &str, pid: u32) {
+        remove_pid_file(self, workspace, pid);
+    }
+
+    fn relaunch(
+        &self,
+        workspace: &str,
+        config: &BackendConfig,
+    ) -> Result<BackendHandle, PortError> {
+        launch(self, workspace, config)
+    }
+}
+
+/// 解析命令中的工作区 ID，缺省为 `default`。ID 会出现在 PID 文件名、日志文件名与数据目录中，
+/// 只接受 ASCII 字母、数字、`-` 与 `_`
+pub(crate) fn workspace_id(id: Option<String>) -> Result<String, PortError> {
+    let Some(id) = id else {
+        return Ok(DEFAULT_WORKSPACE.to_string());
+    };
+    let valid = !id.is_empty()
+        && id.len() <= MAX_WORKSPACE_ID_LEN
+        && id
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_');
+    if !valid {
+        return Err(PortError::InvalidWorkspace { id });
+    }
+    Ok(id)
+}
+
+/// 开启或关闭意外退出后的自动重启；停止后端时会自动关闭，手动启动时按设置重新开启
+pub(crate) fn set_auto_restart_enabled(app: &AppHandle, workspace: &str, enabled: bool) {
+    let state = app.state::<BackendState>();
+    let mut slots = state.lock();
+    let slot = slot_mut(&mut slots, workspace);
+    slot.auto_restart = enabled;
+    if enabled {
+        slot.restart_attempts = 0;
+    }
+}
+
+pub(crate) fn backend_status(app: &AppHandle, workspace: &str) -> BackendStatus {
+    let state = app.state::<BackendState>();
+    let slots = state.lock();
+    match slots.get(workspace) {
+        Some(slot) => slot.status(workspace),
+        None => BackendSlot::default().status(workspace),
+    }
+}
+
+/// 设置中保存的环境变量被修改后调用：所有运行中的后端都需要重启才能使用新值
+pub(crate) fn mark_restart_required(app: &AppHandle) {
+    let state = app.state::<BackendState>();
+    let workspaces: Vec<String> = state
+        .lock()
+        .iter()
+        .filter(|(_, slot)| slot.process.is_some() && !slot.restart_required)
+        .map(|(workspace, _)| workspace.clone())
+        .collect();
+    for workspace in workspaces {
+        let mut slots = state.lock();
+        let slot = slot_mut(&mut slots, &workspace);
+        slot.restart_required = true;
+        let phase = slot.phase;
+        transition(app, slots, &workspace, phase);
+    }
+}
+
+pub(crate) fn is_secret_env(key: &str) -> bool {
+    let key = key.to_ascii_uppercase();
+    SECRET_ENV_SUFFIXES
+        .iter()
+        .any(|suffix| key.ends_with(suffix))
+}
+
+/// 用于展示与日志的环境变量副本，`*_KEY` / `*_TOKEN` 的取值被隐藏
+fn redact_env(env: &HashMap<String, String>) -> BTreeMap<String, String> {
+    env.iter()
+        .map(|(key, value)| {
+            let value = if is_secret_env(key) { REDACTED } else { value };
+            (key.clone(), value.to_string())
+        })
+        .collect()
+}
+
+/// `workspace` 中由 Rust 侧启动且仍在运行的后端
+pub(crate) fn running_backend(app: &AppHandle, workspace: &str) -> Option<BackendHandle> {
+    app.state::<BackendState>()
+        .lock()
+        .get(workspace)
+        .and_then(|slot| slot.process.as_ref())
+        .map(|process| process.handle.clone())
+}
+
+/// 所有工作区中仍在运行的后端的 PID，按 PID 结束进程时跳过
+pub(crate) fn backend_pids(app: &AppHandle) -> Vec<u32> {
+    running_backends(app)
+        .iter()
+        .map(|backend| backend.pid)
+        .collect()
+}
+
+/// 所有工作区中仍在运行的后端
+pub(crate) fn running_backends(app: &AppHandle) -> Vec<BackendHandle> {
+    app.state::<BackendState>()
+        .lock()
+        .values()
+        .filter_map(|slot| slot.process.as_ref())
+        .map(|process| process.handle.clone())
+        .collect()
+}
+
+pub(crate) fn sidecar_failed(error: tauri_plugin_shell::Error) -> PortError {
+    PortError::SidecarFailed {
+        source: error.to_string(),
+    }
+}
+
+fn backend_args(port: u16, config: &BackendConfig) -> Vec<String> {
+    let mut args = vec!["--port".to_string(), port.to_string()];
+    if let Some(data_dir) = &config.data_dir {
+        args.push("--data-dir".to_string());
+        args.push(data_dir.to_string_lossy().into_owned());
+    }
+    args.extend(config.args.iter().cloned());
+    args
+}
+
+/// 启动 `workspace` 的后端并在后台等待其退出；该工作区已有后端在运行时直接返回其句柄，不会重复启动。
+/// 端口已被其他进程或其他工作区占用时拒绝启动。手动启动会重新开始计算自动重启次数
+pub(crate) fn spawn_backend(
+    app: &AppHandle,
+    workspace: &str,
+    config: &BackendConfig,
+) -> Result<BackendHandle, PortError> {
+    let auto_restart = settings::load(app).auto_restart;
+    let state = app.state::<BackendState>();
+    let mut slots = state.lock();
+    let slot = slot_mut(&mut slots, workspace);
+    slot.restart_attempts = 0;
+    slot.auto_restart = auto_restart;
+    drop(slots);
+    launch(app, workspace, config)
+}
+
+/// 补全未指定的端口与数据目录；端口优先使用设置中的端口，其后按顺序扫描并跳过常用开发端口，
+/// 不参考 `find_free_port` 记住的端口。`used` 为其他工作区正在使用的端口；扫描较慢，不要持锁调用
+fn resolve_config(
+    app: &AppHandle,
+    workspace: &str,
+    config: &BackendConfig,
+    settings: &Settings,
+    used: &[u16],
+) -> Result<BackendConfig, PortError> {
+    if let Some(port) = config.port {
+        validate_port(port)?;
+    }
+    let port = match config.port {
+        Some(port) if used.contains(&port) || !can_bind(port) => {
+            return Err(PortError::PortInUse { port });
+        }
+        Some(port) => port,
+        None => {
+            // 设置中的端口由用户指定，即使是常用端口也照常使用
+            let exclude: Vec<u16> = used
+                .iter()
+                .chain(
+                    COMMON_DEV_PORTS
+                        .iter()
+                        .filter(|port| **port != settings.backend_port),
+                )
+                .copied()
+                .collect();
+            let start = settings.backend_port;
+            let end = start.saturating_add(CONFLICT_SEARCH_SPAN);
+            find_free_port_excluding(start, end, &exclude)
+                .ok_or(PortError::NoFreePort { start, end })?
+        }
+    };
+    let data_dir = match &config.data_dir {
+        Some(dir) => Some(dir.clone()),
+        None if workspace == DEFAULT_WORKSPACE => settings.data_dir.clone(),
+        None => match app.path().app_data_dir() {
+            Ok(dir) => Some(dir.join(WORKSPACES_DIR_NAME).join(workspace)),
+            Err(error) => {
+                warn!(workspace, %error, "无法确定工作区数据目录，使用后端默认目录");
+                None
+            }
+        },
+    };
+    Ok(BackendConfig {
+        port: Some(port),
+        data_dir,
+        ..config.clone()
+    })
+}
+
+fn running_process<'a>(slots: &'a Slots, workspace: &str) -> Option<&'a BackendProcess> {
+    slots.get(workspace).and_then(|slot| slot.process.as_ref())
+}
+
+/// 所有工作区正在运行或正在启动的后端的端口
+fn used_ports(slots: &Slots) -> Vec<u16> {
+    slots
+        .values()
+        .filter_map(|slot| {
+            slot.process
+                .as_ref()
+                .map(|process| process.handle.port)
+                .or(slot.launching)
+        })
+        .collect()
+}
+
+fn launch(
+    app: &AppHandle,
+    workspace: &str,
+    config: &BackendConfig,
+) -> Result<BackendHandle, PortError> {
+    let state = app.state::<BackendState>();
+    let used = {
+        let slots = state.lock();
+        if let Some(process) = running_process(&slots, workspace) {
+            return Ok(process.handle.clone());
+        }
+        used_ports(&slots)
+    };
+    // 每次启动都重新读取设置，自动重启时也能用上最新保存的值
+    let settings = settings::load(app);
+    // 扫描端口、校验可执行文件与创建子进程期间都不持锁，避免状态查询与停止等命令被阻塞；
+    // 加锁确认端口仍可用后记下正在启动的端口，其他启动请求不会再选用它
+    let config = resolve_config(app, workspace, config, &settings, &used)?;
+    let port = config.port.unwrap_or_default();
+
+    let mut env = settings.backend_env.clone();
+    if let Some(proxy) = &settings.proxy {
+        for key in PROXY_ENV_KEYS {
+            env.entry(key.to_string()).or_insert_with(|| proxy.clone());
+        }
+    }
+    env.extend(config.env.clone());
+    let redacted = redact_env(&env);
+
+    let command = match &config.executable {
+        Some(path) => {
+            let binary = validate_backend_binary(app, path, false)?;
+            app.shell().command(binary.path)
+        }
+        None => app
+            .shell()
+            .sidecar(BACKEND_SIDECAR)
+            .map_err(sidecar_failed)?,
+    };
+
+    {
+        let mut slots = state.lock();
+        if let Some(process) = running_process(&slots, workspace) {
+            return Ok(process.handle.clone());
+        }
+        if slots
+            .get(workspace)
+            .is_some_and(|slot| slot.launching.is_some())
+        {
+            return Err(PortError::BackendStarting {
+                workspace: workspace.to_string(),
+            });
+        }
+        if used_ports(&slots).contains(&port) || !can_bind(port) {
+            return Err(PortError::PortInUse { port });
+        }
+        slot_mut(&mut slots, workspace).launching = Some(port);
+    }
+
+    begin_session(app, workspace);
+    let spawned = command
+        .args(backend_args(port, &config))
+        .envs(env)
+        .spawn()
+        .map_err(sidecar_failed);
+    let mut slots = state.lock();
+    slot_mut(&mut slots, workspace).launching = None;
+    let (events, child) = spawned?;
+    let handle = BackendHandle {
+        workspace_id: workspace.to_string(),
+        pid: child.pid(),
+        port,
+    };
+    info!(workspace, pid = handle.pid, port, env = ?redacted, "后端已启动");
+    let interval = config
+        .metrics_interval_ms
+        .map_or(DEFAULT_METRICS_INTERVAL, Duration::from_millis);
+    let slot = slot_mut(&mut slots, workspace);
+    slot.process = Some(BackendProcess {
+        child: Box::new(child),
+        handle: handle.clone(),
+        started: Instant::now(),
+    });
+    slot.config = Some(config);
+    slot.last_handle = Some(handle.clone());
+    slot.started_at = Some(now_millis());
+    slot.env = redacted;
+    slot.restart_required = false;
+    transition(app, slots, workspace, BackendPhase::Starting);
+    write_pid_file(app, workspace, handle.pid, port);
+
+    async_runtime::spawn(supervise(app.clone(), handle.clone(), events));
+    async_runtime::spawn(watch_ready(app.clone(), handle.clone()));
+    async_runtime::spawn(sample_backend(app.clone(), handle.clone(), interval));
+    Ok(handle)
+}
+
+/// 端口开始接受连接后把状态切换为 `Running`；期间进程已退出或被停止时不做任何事
+async fn watch_ready(app: AppHandle, handle: BackendHandle) {
+    let ready = wait_for_state(
+        handle.port,
+        PortState::Open,
+        BACKEND_READY_TIMEOUT,
+        WAIT_POLL_INTERVAL,
+    )
+    .await;
+    let state = app.state::<BackendState>();
+    let slots = state.lock();
+    let current = slots.get(&handle.workspace_id).is_some_and(|slot| {
+        slot.phase == BackendPhase::Starting
+            && slot
+                .process
+                .as_ref()
+                .is_some_and(|process| process.handle.pid == handle.pid)
+    });
+    if !current {
+        return;
+    }
+    if ready {
+        transition(&app, slots, &handle.workspace_id, BackendPhase::Running);
+    } else {
+        warn!(
+            workspace = %handle.workspace_id,
+            pid = handle.pid,
+            port = handle.port,
+            "后端未在超时内开始监听端口"
+        );
+    }
+}
+
+/// 等待子进程结束：清理托管状态中的句柄并发送 `backend-exited` 事件。
+/// 句柄仍在托管状态中说明不是 `stop_backend` 结束的：以非零状态退出或被信号结束时按崩溃处理，
+/// 以状态 0 退出视为后端自行正常关闭，不自动重启
+async fn supervise(
+    host: impl BackendHost,
+    handle: BackendHandle,
+    mut events: Receiver<CommandEvent>,
+) {
+    let (workspace, pid) = (handle.workspace_id, handle.pid);
+    let mut stderr = VecDeque::with_capacity(STDERR_TAIL_LINES);
+    while let Some(event) = events.recv().await {
+        let payload = match event {
+            CommandEvent::Terminated(payload) => payload,
+            CommandEvent::Stdout(bytes) => {
+                host.record_output(&workspace, LogStream::Stdout, &bytes);
+                continue;
+            }
+            CommandEvent::Stderr(bytes) => {
+                for line in host.record_output(&workspace, LogStream::Stderr, &bytes) {
+                    if stderr.len() == STDERR_TAIL_LINES {
+                        stderr.pop_front();
+                    }
+                    stderr.push_back(line);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        info!(
+            %workspace,
+            pid,
+            code = ?payload.code,
+            signal = ?payload.signal,
+            "后端已退出"
+        );
+        host.forget_pid(&workspace, pid);
+        let crashed = {
+            let mut slots = host.backends().lock();
+            let slot = slot_mut(&mut slots, &workspace);
+            slot.last_exit_code = payload.code;
+            // 仅清理本进程的句柄，期间可能已启动了新的后端；
+            // 句柄已被取走说明由 `stop_backend` 结束，状态由停止流程切换
+            match slot.process.take_if(|process| process.handle.pid == pid) {
+                Some(_) if payload.code == Some(0) => {
+                    transition(&host, slots, &workspace, BackendPhase::Stopped);
+                    false
+                }
+                Some(process) => {
+                    if process.started.elapsed() >= STABLE_UPTIME {
+                        slot.restart_attempts = 0;
+                    }
+                    transition(&host, slots, &workspace, BackendPhase::Crashed);
+                    true
+                }
+                None => false,
+            }
+        };
+
+        host.emit_event(
+            BACKEND_EXITED_EVENT,
+            BackendExit {
+                workspace_id: workspace.clone(),
+                pid,
+                code: payload.code,
+                signal: payload.signal,
+            },
+        );
+        if crashed {
+            warn!(%workspace, pid, "后端意外退出");
+            host.emit_event(
+                BACKEND_CRASHED_EVENT,
+                BackendCrash {
+                    workspace_id: workspace.clone(),
+                    pid,
+                    code: payload.code,
+                    signal: payload.signal,
+                    stderr: stderr.into_iter().collect(),
+                },
+            );
+            auto_restart(host, workspace).await;
+        }
+        break;
+    }
+}
+
+/// 第 `attempt` 次重启前的等待时间：`base`、2×`base`、4×`base`……，不超过 `RESTART_BACKOFF_MAX`
+fn restart_backoff(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(RESTART_BACKOFF_MAX)
+}
+
+/// 按指数退避重新拉起 `workspace` 的后端，直到成功、被关闭、被手动启动或次数用尽
+async fn auto_restart(host: impl BackendHost, workspace: String) {
+    loop {
+        let (attempt, delay, config) = {
+            let mut slots = host.backends().lock();
+            let slot = slot_mut(&mut slots, &workspace);
+            let Some(config) = slot.config.clone() else {
+                return;
+            };
+            if !slot.auto_restart || slot.process.is_some() {
+                return;
+            }
+            let policy = config.restart_policy;
+            let Some((attempt, delay)) = policy.next_attempt(slot.restart_attempts) else {
+                drop(slots);
+                warn!(%workspace, max_retries = policy.max_retries, "后端自动重启次数已用尽");
+                let payload = BackendGaveUp {
+                    workspace_id: workspace,
+                    attempts: policy.max_retries,
+                };
+                host.emit_event(BACKEND_GAVE_UP_EVENT, payload);
+                return;
+            };
+            slot.restart_attempts = attempt;
+            (attempt, delay, config)
+        };
+
+        info!(
+            %workspace,
+            attempt,
+            delay_ms = delay.as_millis() as u64,
+            "准备自动重启后端"
+        );
+        sleep(delay).await;
+        {
+            // 等待期间可能已关闭自动重启或手动启动了后端
+            let mut slots = host.backends().lock();
+            let slot = slot_mut(&mut slots, &workspace);
+            if !slot.auto_restart || slot.process.is_some() {
+                return;
+            }
+        }
+
+        let result = run_blocking({
+            let host = host.clone();
+            let workspace = workspace.clone();
+            move || host.relaunch(&workspace, &config)
+        })
+        .await;
+        let record = match &result {
+            Ok(handle) => RestartAttempt {
+                attempt,
+                pid: Some(handle.pid),
+                error: None,
+            },
+            Err(error) => {
+                warn!(%workspace, attempt, %error, "自动重启后端失败");
+                RestartAttempt {
+                    attempt,
+                    pid: None,
+                    error: Some(error.to_string()),
+                }
+            }
+        };
+        slot_mut(&mut host.backends().lock(), &workspace).last_restart = Some(record);
+        if let Ok(handle) = result {
+            let payload = BackendRestarted {
+                workspace_id: workspace,
+                attempt,
+                pid: handle.pid,
+                port: handle.port,
+            };
+            host.emit_event(BACKEND_RESTARTED_EVENT, payload);
+            return;
+        }
+    }
+}
+
+/// `stop_backend` 停止后端的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StopOutcome {
+    /// 没有由 Rust 侧启动的后端
+    NotRunning,
+    Graceful,
+    /// 超时后强制结束了整个进程树
+    Forced,
+}
+
+/// 先请求 `workspace` 的后端正常退出（SIGTERM / 不带 `/F` 的 taskkill），在 `timeout` 内等待进程退出且端口释放；
+/// 超时后强制结束整个进程树。无论是否有进程在运行都会关闭自动重启，崩溃后正在等待重启的后端不会再被拉起；
+/// 成功后托管状态中的句柄被清除，之后可以重新启动；
+/// 强制结束后进程仍未退出时返回 `BackendStopFailed` 并保留句柄
+pub(crate) fn shutdown_backend(
+    app: &AppHandle,
+    workspace: &str,
+    timeout: Duration,
+) -> Result<StopOutcome, PortError> {
+    let state = app.state::<BackendState>();
+    let mut slots = state.lock();
+    let slot = slot_mut(&mut slots, workspace);
+    slot.auto_restart = false;
+    let Some(process) = slot.process.take() else {
+        if slot.phase == BackendPhase::Crashed {
+            transition(app, slots, workspace, BackendPhase::Stopped);
+        }
+        return Ok(StopOutcome::NotRunning);
+    };
+    let previous = slot.phase;
+    transition(app, slots, workspace, BackendPhase::Stopping);
+
+    match stop_process(&process.handle, timeout) {
+        Ok(outcome) => {
+            if outcome == StopOutcome::Forced {
+                // 确保 shell 插件持有的子进程句柄也被释放
+                process.child.kill();
+            }
+            // 应用退出时可能等不到 `supervise` 处理退出事件
+            remove_pid_file(app, workspace, process.handle.pid);
+            transition(app, state.lock(), workspace, BackendPhase::Stopped);
+            Ok(outcome)
+        }
+        Err(error) => {
+            let pid = process.handle.pid;
+            state.restore(app, workspace, process, previous);
+            // 进程可能在等待超时期间自行退出：`supervise` 见句柄已被取走不会清理，
+            // 放回的句柄会留下没有监控的“运行中”进程
+            if process_alive(pid) {
+                return Err(error);
+            }
+            let mut slots = state.lock();
+            let slot = slot_mut(&mut slots, workspace);
+            if let Some(process) = slot.process.take_if(|process| process.handle.pid == pid) {
+                process.child.kill();
+                remove_pid_file(app, workspace, pid);
+                transition(app, slots, workspace, BackendPhase::Stopped);
+            }
+            Ok(StopOutcome::Forced)
+        }
+    }
+}
+
+fn stop_process(handle: &BackendHandle, timeout: Duration) -> Result<StopOutcome, PortError> {
+    let (pid, port) = (handle.pid, handle.port);
+    let deadline = Instant::now() + timeout;
+
+    match terminate(pid, false) {
+        Ok(()) | Err(PortError::NoSuchProcess { .. }) => {}
+        // Windows 控制台程序不响应不带 `/F` 的 taskkill，直接进入强制结束
+        Err(error) => warn!(pid, %error, "请求后端正常退出失败"),
+    }
+    wait_for_exit(&[pid], timeout);
+
+    if !process_alive(pid) {
+        while !can_bind(port) && Instant::now() < deadline {
+            thread::sleep(WAIT_POLL_INTERVAL);
+        }
+        return Ok(StopOutcome::Graceful);
+    }
+
+    warn!(pid, "后端未在超时内退出，强制结束进程树");
+    match kill_tree(pid, true, Duration::ZERO) {
+        Ok(_) | Err(PortError::NoSuchProcess { .. }) => {}
+        Err(error) => return Err(error),
+    }
+    wait_for_exit(&[pid], FORCED_EXIT_TIMEOUT);
+    if process_alive(pid) {
+        return Err(PortError::BackendStopFailed { pid });
+    }
+    Ok(StopOutcome::Forced)
+}
+
+/// 有后端在运行时在后台同时停止所有工作区的后端（超时后强制结束），全部停止后调用 `then`。
+/// 没有后端在运行或停止流程已在进行时返回 `false`，此时调用方不必等待
+fn stop_all_then(app: &AppHandle, then: impl FnOnce(&AppHandle) + Send + 'static) -> bool {
+    let state = app.state::<BackendState>();
+    let backends = running_backends(app);
+    if backends.is_empty() || state.exiting.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    // 主动停止，不应触发自动重启
+    for slot in state.lock().values_mut() {
+        slot.auto_restart = false;
+    }
+
+    let app = app.clone();
+    async_runtime::spawn_blocking(move || {
+        // 并行停止，总耗时不超过单个后端的停止超时
+        thread::scope(|scope| {
+            for backend in &backends {
+                let app = &app;
+                scope.spawn(move || {
+                    let workspace = &backend.workspace_id;
+                    if let Err(error) = shutdown_backend(app, workspace, EXIT_STOP_TIMEOUT) {
+                        warn!(%workspace, %error, "退出前停止后端失败");
+                    }
+                });
+            }
+        });
+        then(&app);
+    });
+    true
+}
+
+/// 处理退出请求：停止所有后端后再重新发起退出。返回 `true` 表示已接管退出流程，调用方应阻止本次退出
+pub(crate) fn stop_before_exit(app: &AppHandle, code: Option<i32>) -> bool {
+    stop_all_then(app, move |app| app.exit(code.unwrap_or(0)))
+}
+
+/// 处理主窗口的关闭请求：停止所有后端并确认进程已退出后再关闭窗口。
+/// 返回 `true` 表示已接管关闭流程，调用方应阻止本次关闭
+#[cfg(desktop)]
+pub(crate) fn stop_before_close(window: &tauri::Window) -> bool {
+    let closing = window.clone();
+    stop_all_then(window.app_handle(), move |app| {
+        // 后端已全部停止，再次收到的关闭与退出请求直接放行；之后手动启动的后端仍照常在退出前停止
+        app.state::<BackendState>()
+            .exiting
+            .store(false, Ordering::SeqCst);
+        let _ = closing.close();
+    })
+}
+
+/// 进程退出前的兜底：停止失败或未经退出请求直接退出时，强制结束所有仍在运行的后端
+pub(crate) fn kill_on_exit(app: &AppHandle) {
+    let processes: Vec<BackendProcess> = app
+        .state::<BackendState>()
+        .lock()
+        .values_mut()
+        .filter_map(|slot| slot.process.take())
+        .collect();
+    for process in processes {
+        let (workspace, pid) = (&process.handle.workspace_id, process.handle.pid);
+        warn!(%workspace, pid, "应用退出时后端仍在运行，强制结束");
+        if let Err(error) = kill_tree(pid, true, Duration::ZERO) {
+            warn!(%workspace, pid, %error, "强制结束后端失败");
+        }
+        remove_pid_file(app, workspace, pid);
+        process.child.kill();
+    }
+}
+
+/// 结束仍占用 `port` 的其他进程（例如上次崩溃残留的后端），在 `timeout` 内等待端口释放
+fn free_port(port: u16, timeout: Duration) -> Result<(), PortError> {
+    if can_bind(port) {
+        return Ok(());
+    }
+    warn!(port, "端口仍被占用，结束占用进程");
+    let options = KillOptions {
+        grace: timeout,
+        allow_self: false,
+        // 本工作区的后端已停止，其他工作区的后端不会使用同一端口
+        backends: Vec::new(),
+        include_children: true,
+        expected_names: None,
+        protocol: Protocol::Tcp,
+        dry_run: false,
+    };
+    kill_port_listeners(port, &options)?;
+
+    let deadline = Instant::now() + timeout;
+    while !can_bind(port) {
+        if Instant::now() >= deadline {
+            return Err(PortError::PortNotFreed {
+                port,
+                timeout_ms: timeout.as_millis() as u64,
+            });
+        }
+        thread::sleep(WAIT_POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// 重启流程中停止与启动后端的方式。应用中由 [`AppHandle`] 实现；
+/// 测试中用启动普通子进程的实现代替，不需要 Tauri 运行时
+pub(crate) trait BackendSpawner {
+    /// 停止 `workspace` 的后端，见 [`shutdown_backend`]
+    fn stop(&self, workspace: &str, timeout: Duration) -> Result<StopOutcome, PortError>;
+    /// 启动 `workspace` 的后端，见 [`spawn_backend`]
+    fn spawn(&self, workspace: &str, config: &BackendConfig) -> Result<BackendHandle, PortError>;
+}
+
+impl BackendSpawner for AppHandle {
+    fn stop(&self, workspace: &str, timeout: Duration) -> Result<StopOutcome, PortError> {
+        shutdown_backend(self, workspace, timeout)
+    }
+
+    fn spawn(&self, workspace: &str, config: &BackendConfig) -> Result<BackendHandle, PortError> {
+        spawn_backend(self, workspace, config)
+    }
+}
+
+/// 停止旧进程，在 `timeout` 内等待端口释放（必要时结束占用端口的其他进程）后再启动新进程；
+/// 任一步失败都直接返回，不会启动新进程
+fn restart_sequence(
+    spawner: &impl BackendSpawner,
+    workspace: &str,
+    config: &BackendConfig,
+    timeout: Duration,
+) -> Result<BackendHandle, PortError> {
+    spawner.stop(workspace, timeout)?;
+    if let Some(port) = config.port {
+        free_port(port, timeout)?;
+    }
+    spawner.spawn(workspace, config)
+}
+
+/// 重启 `workspace` 的后端，新进程开始接受连接后才返回。`port` 覆盖上次配置中的端口，
+/// 从未启动过时只需提供端口。旧进程或占用端口的其他进程无法结束时直接失败，不会启动第二个实例
+pub(crate) async fn restart_with_last_config(
+    app: AppHandle,
+    workspace: String,
+    port: Option<u16>,
+    timeout: Duration,
+) -> Result<BackendHandle, PortError> {
+    let last = app
+        .state::<BackendState>()
+        .lock()
+        .get(&workspace)
+        .and_then(|slot| slot.config.clone());
+    let config = match (last, port) {
+        (Some(config), None) => config,
+        (Some(config), Some(port)) => BackendConfig {
+            port: Some(port),
+            ..config
+        },
+        (None, Some(port)) => BackendConfig {
+            port: Some(port),
+            data_dir: None,
+            args: Vec::new(),
+            restart_policy: RestartPolicy::default(),
+            env: HashMap::new(),
+            executable: None,
+            metrics_interval_ms: None,
+        },
+        (None, None) => return Err(PortError::BackendNotConfigured),
+    };
+    let _ = app.emit(BACKEND_RESTARTING_EVENT, workspace.clone());
+
+    let handle = run_blocking({
+        let app = app.clone();
+        move || restart_sequence(&app, &workspace, &config, timeout)
+    })
+    .await?;
+
+    let ready = wait_for_state(
+        handle.port,
+        PortState::Open,
+        BACKEND_READY_TIMEOUT,
+        WAIT_POLL_INTERVAL,
+    )
+    .await;
+    if !ready {
+        return Err(PortError::BackendNotReady {
+            port: handle.port,
+            timeout_ms: BACKEND_READY_TIMEOUT.as_millis() as u64,
+        });
+    }
+    let _ = app.emit(BACKEND_READY_EVENT, handle.clone());
+    Ok(handle)
+}
+
+/// 启动后端并等待其真正可用：端口开始接受连接且 `health_path` 返回 2xx 后才返回，
+/// 期间依次发送 `spawned`、`port-open`、`healthy` 进度事件。超时时结束启动到一半的进程；
+/// 超时或进程在启动中退出时，错误中附带本次启动以来的 stderr
+pub(crate) async fn start_and_wait(
+    app: AppHandle,
+    workspace: String,
+    config: BackendConfig,
+    health_path: String,
+    timeout: Duration,
+) -> Result<BackendHandle, PortError> {
+    let since = now_millis();
+    let deadline = Instant::now() + timeout;
+    let handle = run_blocking({
+        let app = app.clone();
+        let workspace = workspace.clone();
+        move || spawn_backend(&app, &workspace, &config)
+    })
+    .await?;
+    let progress = |stage| {
+        let payload = StartupProgress {
+            workspace_id: workspace.clone(),
+            stage,
+            pid: handle.pid,
+            port: handle.port,
+        };
+        let _ = app.emit(BACKEND_STARTUP_EVENT, payload);
+    };
+    let stderr = || {
+        app.state::<BackendLog>()
+            .stderr_since(&workspace, since, STDERR_TAIL_LINES)
+    };
+    progress(StartupStage::Spawned);
+
+    let mut port_open = false;
+    loop {
+        if running_backend(&app, &workspace).is_none_or(|backend| backend.pid != handle.pid) {
+            let exit_code = app
+                .state::<BackendState>()
+                .lock()
+                .get(&workspace)
+                .and_then(|slot| slot.last_exit_code);
+            return Err(PortError::ExitedDuringStartup(StartupFailure {
+                port: handle.port,
+                timeout_ms: timeout.as_millis() as u64,
+                exit_code,
+                stderr: stderr(),
+            }));
+        }
+        if !port_open && accepts_connections(handle.port).await {
+            port_open = true;
+            progress(StartupStage::PortOpen);
+        }
+        if port_open {
+            let probe_timeout = deadline
+                .saturating_duration_since(Instant::now())
+                .clamp(WAIT_POLL_INTERVAL, STARTUP_PROBE_TIMEOUT);
+            if probe_ready(&app, None, handle.port, &health_path, probe_timeout).await? {
+                progress(StartupStage::Healthy);
+                return Ok(handle);
+            }
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+        sleep(WAIT_POLL_INTERVAL).await;
+    }
+
+    warn!(
+        %workspace,
+        pid = handle.pid,
+        port = handle.port,
+        port_open,
+        "后端未在超时内就绪，结束进程"
+    );
+    let stopped = run_blocking({
+        let app = app.clone();
+        let workspace = workspace.clone();
+        move || shutdown_backend(&app, &workspace, Duration::ZERO)
+    })
+    .await;
+    if let Err(error) = stopped {
+        warn!(pid = handle.pid, %error, "结束未就绪的后端失败");
+    }
+    Err(PortError::StartupTimedOut(StartupFailure {
+        port: handle.port,
+        timeout_ms: timeout.as_millis() as u64,
+        exit_code: None,
+        stderr: stderr(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::process::{Command, Stdio};
+    use std::sync::Arc;
+
+    use serde_json::Value;
+    use tauri_plugin_shell::process::TerminatedPayload;
+
+    use super::*;
+
+    const HOLDER_ENV: &str = "OPENREVIEW_TEST_PORT_HOLDER";
+    const EXIT_CODE_ENV: &str = "OPENREVIEW_TEST_EXIT_CODE";
+
+    /// 由 `stop_leaves_port_free` 以子进程方式运行，代替后端：监听临时端口，输出端口号后等待被结束
+    #[test]
+    #[ignore]
+    fn port_holder() {
+        if std::env::var_os(HOLDER_ENV).is_none() {
+            return;
+        }
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        println!("PORT={}", listener.local_addr().unwrap().port());
+        thread::sleep(Duration::from_secs(30));
+    }
+
+    /// 以子进程方式运行 `port_holder`，返回子进程与其监听的端口
+    fn spawn_port_holder() -> (std::process::Child, u16) {
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--ignored",
+                "--exact",
+                "backend::tests::port_holder",
+                "--nocapture",
+            ])
+            .env(HOLDER_ENV, "1")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let port = BufReader::new(child.stdout.take().unwrap())
+            .lines()
+            .map_while(Result::ok)
+            // 测试框架在同一行先输出测试名
+            .find_map(|line| line.rsplit_once("PORT=")?.1.parse::<u16>().ok())
+            .unwrap();
+        (child, port)
+    }
+
+    #[test]
+    fn stop_leaves_port_free() {
+        let (mut child, port) = spawn_port_holder();
+        let handle = BackendHandle {
+            workspace_id: DEFAULT_WORKSPACE.to_string(),
+            pid: child.id(),
+            port,
+        };
+        assert!(!can_bind(port));
+
+        // 回收子进程，否则退出后的僵尸进程仍被视为存活
+        let reaper = thread::spawn(move || child.wait());
+        let outcome = stop_process(&handle, Duration::from_secs(5)).unwrap();
+        // Windows 控制台程序不响应不带 `/F` 的 taskkill，会走强制结束
+        if cfg!(unix) {
+            assert_eq!(outcome, StopOutcome::Graceful);
+        }
+        assert!(can_bind(port));
+        assert!(!reaper.join().unwrap().unwrap().success());
+    }
+
+    #[test]
+    fn stopping_an_exited_backend_is_idempotent() {
+        let (mut child, port) = spawn_port_holder();
+        let handle = BackendHandle {
+            workspace_id: DEFAULT_WORKSPACE.to_string(),
+            pid: child.id(),
+            port,
+        };
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        // 进程已自行退出：不需要强制结束，重复停止结果相同
+        for _ in 0..2 {
+            let outcome = stop_process(&handle, Duration::from_secs(5)).unwrap();
+            assert_eq!(outcome, StopOutcome::Graceful);
+            assert!(can_bind(port));
+        }
+    }
+
+    /// 由 `start_dummy` 以子进程方式运行，代替立即退出的后端：向 stderr 输出一行后以指定状态退出
+    #[test]
+    #[ignore]
+    fn exit_with_code() {
+        let Some(code) = std::env::var(EXIT_CODE_ENV)
+            .ok()
+            .and_then(|code| code.parse().ok())
+        else {
+            return;
+        };
+        eprintln!("dummy exiting with {code}");
+        std::process::exit(code);
+    }
+
+    struct DummyChild;
+
+    impl BackendChild for DummyChild {
+        fn kill(self: Box<Self>) {}
+    }
+
+    /// 启动以 `code` 退出的子进程并登记为 `workspace` 正在启动的后端，
+    /// 返回句柄与按 shell 插件的格式转发的输出与退出事件
+    fn start_dummy(
+        backends: &BackendState,
+        workspace: &str,
+        code: i32,
+    ) -> (BackendHandle, Receiver<CommandEvent>) {
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--ignored",
+                "--exact",
+                "backend::tests::exit_with_code",
+                "--nocapture",
+            ])
+            .env(EXIT_CODE_ENV, code.to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let handle = BackendHandle {
+            workspace_id: workspace.to_string(),
+            pid: child.id(),
+            port: 0,
+        };
+        let (sender, events) = async_runtime::channel(16);
+        let stderr = child.stderr.take().unwrap();
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                let _ = sender.blocking_send(CommandEvent::Stderr(line.into_bytes()));
+            }
+            let status = child.wait().unwrap();
+            #[cfg(unix)]
+            let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+            #[cfg(not(unix))]
+            let signal = None;
+            let payload = TerminatedPayload {
+                code: status.code(),
+                signal,
+            };
+            let _ = sender.blocking_send(CommandEvent::Terminated(payload));
+        });
+
+        let mut slots = backends.lock();
+        let slot = slot_mut(&mut slots, workspace);
+        slot.process = Some(BackendProcess {
+            child: Box::new(DummyChild),
+            handle: handle.clone(),
+            started: Instant::now(),
+        });
+        slot.last_handle = Some(handle.clone());
+        slot.phase = BackendPhase::Starting;
+        (handle, events)
+    }
+
+    /// 记录所发送事件的 `BackendHost`；`relaunch` 启动以 `exit_code` 退出的子进程代替后端
+    #[derive(Clone)]
+    struct TestHost {
+        backends: Arc<BackendState>,
+        events: Arc<Mutex<Vec<(String, Value)>>>,
+        exit_code: i32,
+    }
+
+    impl TestHost {
+        fn new(exit_code: i32) -> Self {
+            Self {
+                backends: Arc::default(),
+                events: Arc::default(),
+                exit_code,
+            }
+        }
+
+        fn events(&self) -> Vec<(String, Value)> {
+            self.events.lock().unwrap().clone()
+        }
+
+        fn event_names(&self) -> Vec<String> {
+            self.events().into_iter().map(|(name, _)| name).collect()
+        }
+    }
+
+    impl BackendHost for TestHost {
+        fn backends(&self) -> &BackendState {
+            &self.backends
+        }
+
+        fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+            let payload = serde_json::to_value(payload).unwrap();
+            self.events
+                .lock()
+                .unwrap()
+                .push((event.to_string(), payload));
+        }
+
+        fn record_output(&self, _workspace: &str, _stream: LogStream, bytes: &[u8]) -> Vec<String> {
+            String::from_utf8_lossy(bytes)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+
+        fn forget_pid(&self, _workspace: &str, _pid: u32) {}
+
+        fn relaunch(
+            &self,
+            workspace: &str,
+            _config: &BackendConfig,
+        ) -> Result<BackendHandle, PortError> {
+            let (handle, events) = start_dummy(&self.backends, workspace, self.exit_code);
+            async_runtime::spawn(supervise(self.clone(), handle.clone(), events));
+            Ok(handle)
+        }
+    }
+
+    #[test]
+    fn crashed_process_emits_exit_and_crash_events() {
+        let host = TestHost::new(3);
+        let (handle, events) = start_dummy(&host.backends, DEFAULT_WORKSPACE, 3);
+        async_runtime::block_on(supervise(host.clone(), handle.clone(), events));
+
+        assert_eq!(
+            host.event_names(),
+            vec![
+                BACKEND_STATUS_EVENT,
+                BACKEND_EXITED_EVENT,
+                BACKEND_CRASHED_EVENT
+            ]
+        );
+        let events = host.events();
+        let exit = &events[1].1;
+        assert_eq!(exit["workspaceId"], DEFAULT_WORKSPACE);
+        assert_eq!(exit["pid"], handle.pid);
+        assert_eq!(exit["code"], 3);
+        assert_eq!(events[2].1["stderr"][0], "dummy exiting with 3");
+
+        let slots = host.backends.lock();
+        let slot = &slots[DEFAULT_WORKSPACE];
+        assert!(slot.process.is_none());
+        assert_eq!(slot.phase, BackendPhase::Crashed);
+        assert_eq!(slot.last_exit_code, Some(3));
+    }
+
+    #[test]
+    fn clean_exit_emits_only_the_exit_event() {
+        let host = TestHost::new(0);
+        let (handle, events) = start_dummy(&host.backends, DEFAULT_WORKSPACE, 0);
+        async_runtime::block_on(supervise(host.clone(), handle, events));
+
+        assert_eq!(
+            host.event_names(),
+            vec![BACKEND_STATUS_EVENT, BACKEND_EXITED_EVENT]
+        );
+        assert_eq!(host.events()[1].1["code"], 0);
+        assert_eq!(
+            host.backends.lock()[DEFAULT_WORKSPACE].phase,
+            BackendPhase::Stopped
+        );
+    }
+
+    #[test]
+    fn stopped_process_is_not_treated_as_a_crash() {
+        let host = TestHost::new(1);
+        let (handle, events) = start_dummy(&host.backends, DEFAULT_WORKSPACE, 1);
+        // `stop_backend` 先取走句柄再结束进程
+        let stopped = slot_mut(&mut host.backends.lock(), DEFAULT_WORKSPACE)
+            .process
+            .take();
+        assert!(stopped.is_some());
+        async_runtime::block_on(supervise(host.clone(), handle, events));
+
+        assert_eq!(host.event_names(), vec![BACKEND_EXITED_EVENT]);
+        assert_eq!(host.events()[0].1["code"], 1);
+    }
+
+    /// 记录调用顺序的 `BackendSpawner`；`spawn` 启动测试程序本身（`--list` 后立即退出）代替后端
+    #[derive(Default)]
+    struct MockSpawner {
+        calls: Mutex<Vec<&'static str>>,
+        stop_fails: bool,
+        children: Mutex<Vec<std::process::Child>>,
+    }
+
+    impl BackendSpawner for MockSpawner {
+        fn stop(&self, _workspace: &str, _timeout: Duration) -> Result<StopOutcome, PortError> {
+            self.calls.lock().unwrap().push("stop");
+            if self.stop_fails {
+                return Err(PortError::BackendStopFailed { pid: 1 });
+            }
+            Ok(StopOutcome::Graceful)
+        }
+
+        fn spawn(
+            &self,
+            workspace: &str,
+            config: &BackendConfig,
+        ) -> Result<BackendHandle, PortError> {
+            self.calls.lock().unwrap().push("spawn");
+            let child = Command::new(std::env::current_exe().unwrap())
+                .arg("--list")
+                .stdout(Stdio::null())
+                .spawn()
+                .unwrap();
+            let handle = BackendHandle {
+                workspace_id: workspace.to_string(),
+                pid: child.id(),
+                port: config.port.unwrap(),
+            };
+            self.children.lock().unwrap().push(child);
+            Ok(handle)
+        }
+    }
+
+    impl MockSpawner {
+        fn calls(&self) -> Vec<&'static str> {
+            for child in self.children.lock().unwrap().iter_mut() {
+                child.wait().unwrap();
+            }
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    fn config(port: u16) -> BackendConfig {
+        BackendConfig {
+            port: Some(port),
+            data_dir: None,
+            args: Vec::new(),
+            restart_policy: RestartPolicy::default(),
+            env: HashMap::new(),
+            executable: None,
+            metrics_interval_ms: None,
+        }
+    }
+
+    fn free_local_port() -> u16 {
+        TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[test]
+    fn restart_stops_before_spawning_on_the_freed_port() {
+        let spawner = MockSpawner::default();
+        let port = free_local_port();
+        let handle = restart_sequence(
+            &spawner,
+            DEFAULT_WORKSPACE,
+            &config(port),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        assert_eq!(handle.port, port);
+        assert_eq!(handle.workspace_id, DEFAULT_WORKSPACE);
+        assert_eq!(spawner.calls(), vec!["stop", "spawn"]);
+    }
+
+    #[test]
+    fn restart_kills_a_leftover_port_holder_before_spawning() {
+        let spawner = MockSpawner::default();
+        let (mut child, port) = spawn_port_holder();
+        let reaper = thread::spawn(move || child.wait());
+        let handle = restart_sequence(
+            &spawner,
+            DEFAULT_WORKSPACE,
+            &config(port),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(handle.port, port);
+        assert!(!reaper.join().unwrap().unwrap().success());
+        assert_eq!(spawner.calls(), vec!["stop", "spawn"]);
+    }
+
+    #[test]
+    fn restart_does_not_spawn_when_the_port_cannot_be_freed() {
+        let spawner = MockSpawner::default();
+        // 端口由本进程持有，`free_port` 拒绝结束自身
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let error = restart_sequence(
+            &spawner,
+            DEFAULT_WORKSPACE,
+            &config(port),
+            Duration::from_millis(200),
+        )
+        .unwrap_err();
+        assert!(matches!(error, PortError::WouldKillSelf), "{error:?}");
+        assert_eq!(spawner.calls(), vec!["stop"]);
+        drop(listener);
+    }
+
+    #[test]
+    fn restart_does_not_spawn_when_stop_fails() {
+        let spawner = MockSpawner {
+            stop_fails: true,
+            ..MockSpawner::default()
+        };
+        let error = restart_sequence(
+            &spawner,
+            DEFAULT_WORKSPACE,
+            &config(free_local_port()),
+            Duration::from_secs(1),
+        )
+        .unwrap_err();
+        assert!(matches!(error, PortError::BackendStopFailed { .. }));
+        assert_eq!(spawner.calls(), vec!["stop"]);
+    }
+
+    /// 用真实的 Tauri 应用与 shell 插件启动回显参数后立即退出的 sidecar。需要图形环境（如 `xvfb-run`），
+    /// 默认不编译，用 `cargo test --features sidecar-tests` 运行
+    #[cfg(all(feature = "sidecar-tests", target_os = "linux"))]
+    #[test]
+    fn echo_sidecar_receives_the_port_and_reports_its_exit() {
+        use std::os::unix::fs::PermissionsExt;
+        use tauri::Listener;
+
+        // 开发构建从当前可执行文件所在的目录解析 sidecar
+        let sidecar = std::env::current_exe()
+            .unwrap()
+            .with_file_name(BACKEND_SIDECAR);
+        std::fs::write(&sidecar, "#!/bin/sh\necho \"echo-sidecar $*\"\n").unwrap();
+        std::fs::set_permissions(&sidecar, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let app = tauri::Builder::default()
+            .any_thread()
+            .plugin(tauri_plugin_shell::init())
+            .plugin(tauri_plugin_store::Builder::default().build())
+            .manage(BackendState::default())
+            .manage(BackendLog::default())
+            .manage(crate::metrics::BackendMetrics::default())
+            .build(tauri::generate_context!())
+            .unwrap();
+        let app = app.handle().clone();
+        let (sender, exited) = std::sync::mpsc::channel();
+        app.listen(BACKEND_EXITED_EVENT, move |event| {
+            let _ = sender.send(event.payload().to_string());
+        });
+
+        let port = free_local_port();
+        let handle = spawn_backend(&app, DEFAULT_WORKSPACE, &config(port)).unwrap();
+        assert_eq!(handle.port, port);
+        let payload = exited.recv_timeout(Duration::from_secs(10)).unwrap();
+        let payload: Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(payload["pid"], handle.pid);
+        assert_eq!(payload["code"], 0);
+
+        let expected = format!("echo-sidecar --port {port}");
+        let lines = app.state::<BackendLog>().tail(DEFAULT_WORKSPACE, 10);
+        assert!(lines.iter().any(|line| line.line == expected));
+        assert_eq!(
+            backend_status(&app, DEFAULT_WORKSPACE).state,
+            BackendPhase::Stopped
+        );
+        std::fs::remove_file(sidecar).unwrap();
+    }
+
+    #[test]
+    fn always_crashing_backend_gives_up_after_max_retries() {
+        let host = TestHost::new(1);
+        let policy = RestartPolicy {
+            max_retries: 3,
+            backoff_ms: 1,
+        };
+        {
+            let mut slots = host.backends.lock();
+            let slot = slot_mut(&mut slots, DEFAULT_WORKSPACE);
+            slot.config = Some(BackendConfig {
+                restart_policy: policy,
+                ..config(0)
+            });
+        }
+        // 首次启动与每次重启的进程都立即以状态 1 退出
+        let (handle, events) = start_dummy(&host.backends, DEFAULT_WORKSPACE, 1);
+        async_runtime::spawn(supervise(host.clone(), handle, events));
+
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while !host
+            .event_names()
+            .iter()
+            .any(|name| name == BACKEND_GAVE_UP_EVENT)
+        {
+            assert!(Instant::now() < deadline, "{:?}", host.event_names());
+            thread::sleep(WAIT_POLL_INTERVAL);
+        }
+        let events = host.events();
+        let count = |event: &str| events.iter().filter(|(name, _)| name == event).count();
+        assert_eq!(count(BACKEND_CRASHED_EVENT), 4);
+        assert_eq!(count(BACKEND_RESTARTED_EVENT), 3);
+        assert_eq!(count(BACKEND_GAVE_UP_EVENT), 1);
+        let attempts: Vec<&Value> = events
+            .iter()
+            .filter(|(name, _)| name == BACKEND_RESTARTED_EVENT)
+            .map(|(_, payload)| &payload["attempt"])
+            .collect();
+        assert_eq!(attempts, [1, 2, 3]);
+        let (_, gave_up) = events.last().unwrap();
+        assert_eq!(gave_up["attempts"], 3);
+
+        let slots = host.backends.lock();
+        let slot = &slots[DEFAULT_WORKSPACE];
+        assert_eq!(slot.phase, BackendPhase::Crashed);
+        assert_eq!(slot.restart_attempts, 3);
+    }
+
+    #[test]
+    fn restart_policy_counts_attempts_until_it_gives_up() {
+        let policy = RestartPolicy {
+            max_retries: 4,
+            backoff_ms: 500,
+        };
+        // 每次重启后都立即崩溃：按 `auto_restart` 的方式累计次数，直到策略放弃
+        let mut attempts = 0;
+        let mut delays = Vec::new();
+        while let Some((attempt, delay)) = policy.next_attempt(attempts) {
+            assert_eq!(attempt, attempts + 1);
+            attempts = attempt;
+            delays.push(delay.as_millis());
+        }
+        assert_eq!(attempts, 4);
+        assert_eq!(delays, vec![500, 1000, 2000, 4000]);
+
+        let disabled = RestartPolicy {
+            max_retries: 0,
+            ..RestartPolicy::default()
+        };
+        assert_eq!(disabled.next_attempt(0), None);
+        assert_eq!(
+            RestartPolicy::default().next_attempt(DEFAULT_MAX_RETRIES),
+            None
+        );
+    }
+
+    #[test]
+    fn restart_backoff_doubles_up_to_the_cap() {
+        let base = Duration::from_secs(1);
+        let delays: Vec<u64> = (1..=7)
+            .map(|attempt| restart_backoff(base, attempt).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(restart_backoff(base, 0), base);
+        assert_eq!(restart_backoff(base, u32::MAX), RESTART_BACKOFF_MAX);
+        assert_eq!(restart_backoff(Duration::ZERO, 3), Duration::ZERO);
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::warn;
+
+use crate::backend::DEFAULT_WORKSPACE;
+use crate::ports::PortError;
+
+/// 后端每输出一行发送一次，载荷为 [`LogLine`]
+pub(crate) const BACKEND_LOG_EVENT: &str = "backend://log";
+/// 内存中保留的最近行数，供新打开的日志面板回填
+const LOG_BUFFER_LINES: usize = 2000;
+/// 单行最大字节数，超出部分截断，避免撑大 IPC 载荷
+const MAX_LINE_BYTES: usize = 4096;
+/// 应用日志目录下存放后端输出的子目录
+const LOG_DIR_NAME: &str = "backend";
+const LOG_FILE_PREFIX: &str = "backend-";
+const LOG_FILE_SUFFIX: &str = ".log";
+/// 单个日志文件达到该大小后滚动到新文件
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// 每个工作区所有会话合计保留的日志文件数
+const MAX_LOG_FILES: usize = 5;
+/// `read_backend_log` 单次最多返回的字节数
+const MAX_READ_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// 后端输出的一行；`timestamp` 为收到该行时的 Unix 毫秒时间戳
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    pub workspace_id: String,
+    pub stream: LogStream,
+    pub line: String,
+    pub timestamp: u64,
+}
+
+/// 磁盘上的一个后端日志文件；`modified` 为 Unix 毫秒时间戳
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFileInfo {
+    pub name: String,
+    pub workspace_id: String,
+    pub size: u64,
+    pub modified: Option<u64>,
+}
+
+/// `read_backend_log` 读取到的一段内容；`next_offset` 为下次读取的起点
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogChunk {
+    pub content: String,
+    pub next_offset: u64,
+    pub eof: bool,
+}
+
+/// 某个工作区当前会话正在写入的日志文件
+struct LogWriter {
+    dir: PathBuf,
+    workspace: String,
+    /// 会话开始时的 Unix 毫秒时间戳，作为文件名前缀
+    session: u64,
+    index: u32,
+    file: File,
+    written: u64,
+}
+
+impl LogWriter {
+    fn open(dir: &Path, workspace: &str, session: u64, index: u32) -> std::io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let name = log_file_name(workspace, session, index);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(name))?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            workspace: workspace.to_string(),
+            session,
+            index,
+            file,
+            written,
+        })
+    }
+
+    fn write_line(&mut self, entry: &LogLine) -> std::io::Result<()> {
+        let stream = match entry.stream {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+        };
+        let text = format!("{} [{stream}] {}\n", entry.timestamp, entry.line);
+        if self.written > 0 && self.written + text.len() as u64 > MAX_LOG_FILE_BYTES {
+            *self = Self::open(&self.dir, &self.workspace, self.session, self.index + 1)?;
+            prune_log_files(&self.dir, &self.workspace);
+        }
+        self.file.write_all(text.as_bytes())?;
+        self.written += text.len() as u64;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct LogBuffer {
+    lines: VecDeque<LogLine>,
+    /// 以工作区 ID 为键
+    writers: HashMap<String, LogWriter>,
+}
+
+/// 所有工作区后端输出的环形缓冲区与日志文件，通过 `.manage()` 注册为全局状态；
+/// 写入与滚动都在同一把锁内完成，滚动期间到达的行不会交错或丢失
+#[derive(Default)]
+pub(crate) struct BackendLog {
+    inner: Mutex<LogBuffer>,
+}
+
+impl BackendLog {
+    fn lock(&self) -> MutexGuard<'_, LogBuffer> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// `workspace` 最近的 `count` 行，按时间先后排列
+    pub(crate) fn tail(&self, workspace: &str, count: usize) -> Vec<LogLine> {
+        let buffer = self.lock();
+        let mut lines: Vec<LogLine> = buffer
+            .lines
+            .iter()
+            .rev()
+            .filter(|entry| entry.workspace_id == workspace)
+            .take(count)
+            .cloned()
+            .collect();
+        lines.reverse();
+        lines
+    }
+
+    /// `workspace` 在 `since`（Unix 毫秒时间戳）之后输出的最后 `count` 行 stderr
+    pub(crate) fn stderr_since(&self, workspace: &str, since: u64, count: usize) -> Vec<String> {
+        let buffer = self.lock();
+        let mut lines: Vec<String> = buffer
+            .lines
+            .iter()
+            .rev()
+            .take_while(|entry| entry.timestamp >= since)
+            .filter(|entry| entry.workspace_id == workspace && entry.stream == LogStream::Stderr)
+            .take(count)
+            .map(|entry| entry.line.clone())
+            .collect();
+        lines.reverse();
+        lines
+    }
+}
+
+fn log_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_log_dir()
+        .ok()
+        .map(|dir| dir.join(LOG_DIR_NAME))
+}
+
+fn log_file_failed(error: std::io::Error) -> PortError {
+    PortError::LogFileFailed {
+        source: error.to_string(),
+    }
+}
+
+/// 默认工作区为 `backend-<会话>-<序号>.log`，其他工作区在序号后加 `.<ID>`；
+/// 工作区 ID 不含 `.`，可以从文件名中无歧义地取回
+fn log_file_name(workspace: &str, session: u64, index: u32) -> String {
+    if workspace == DEFAULT_WORKSPACE {
+        format!("{LOG_FILE_PREFIX}{session}-{index:03}{LOG_FILE_SUFFIX}")
+    } else {
+        format!("{LOG_FILE_PREFIX}{session}-{index:03}.{workspace}{LOG_FILE_SUFFIX}")
+    }
+}
+
+fn is_log_file_name(name: &str) -> bool {
+    name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX)
+}
+
+fn log_file_workspace(name: &str) -> &str {
+    name.strip_prefix(LOG_FILE_PREFIX)
+        .and_then(|rest| rest.strip_suffix(LOG_FILE_SUFFIX))
+        .and_then(|stem| stem.split_once('.'))
+        .map_or(DEFAULT_WORKSPACE, |(_, workspace)| workspace)
+}
+
+/// 按文件名（即会话时间与序号）升序排列的日志文件
+fn log_file_names(dir: &Path) -> std::io::Result<Vec<String>> {
+    let mut names: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| is_log_file_name(name))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// 只清理 `workspace` 自己的旧文件，不影响其他工作区正在写入的日志
+fn prune_log_files(dir: &Path, workspace: &str) {
+    let Ok(mut names) = log_file_names(dir) else {
+        return;
+    };
+    names.retain(|name| log_file_workspace(name) == workspace);
+    let excess = names.len().saturating_sub(MAX_LOG_FILES);
+    for name in &names[..excess] {
+        if let Err(error) = fs::remove_file(dir.join(name)) {
+            warn!(%name, %error, "删除旧的后端日志失败");
+        }
+    }
+}
+
+fn truncate_line(line: &str) -> String {
+    if line.len() <= MAX_LINE_BYTES {
+        return line.to_string();
+    }
+    let mut end = MAX_LINE_BYTES;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &line[..end])
+}
+
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// 每次启动 `workspace` 的后端时调用：之后的输出写入以当前时间命名的新日志文件。
+/// 日志目录不可用时只保留内存中的输出
+pub(crate) fn begin_session(app: &AppHandle, workspace: &str) {
+    let writer = log_dir(app).and_then(|dir| {
+        LogWriter::open(&dir, workspace, now_millis(), 0)
+            .map_err(|error| warn!(workspace, %error, "无法创建后端日志文件"))
+            .ok()
+            .inspect(|_| prune_log_files(&dir, workspace))
+    });
+    let state = app.state::<BackendLog>();
+    let mut buffer = state.lock();
+    if let Some(writer) = writer {
+        buffer.writers.insert(workspace.to_string(), writer);
+    } else {
+        buffer.writers.remove(workspace);
+    }
+}
+
+/// 记录 `workspace` 的 sidecar 输出的一段内容并逐行发送 `backend://log` 事件，返回拆分后的行。
+/// 非 UTF-8 内容按有损方式转换
+pub(crate) fn record(
+    app: &AppHandle,
+    workspace: &str,
+    stream: LogStream,
+    bytes: &[u8],
+) -> Vec<String> {
+    let timestamp = now_millis();
+    let entries: Vec<LogLine> = String::from_utf8_lossy(bytes)
+        .lines()
+        .map(|line| LogLine {
+            workspace_id: workspace.to_string(),
+            stream,
+            line: truncate_line(line),
+            timestamp,
+        })
+        .collect();
+
+    {
+        let state = app.state::<BackendLog>();
+        let mut buffer = state.lock();
+        for entry in &entries {
+            if buffer.lines.len() == LOG_BUFFER_LINES {
+                buffer.lines.pop_front();
+            }
+            buffer.lines.push_back(entry.clone());
+            if let Some(writer) = buffer.writers.get_mut(workspace) {
+                if let Err(error) = writer.write_line(entry) {
+                    warn!(workspace, %error, "写入后端日志失败，停止写入文件");
+                    buffer.writers.remove(workspace);
+                }
+            }
+        }
+    }
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let _ = app.emit(BACKEND_LOG_EVENT, entry.clone());
+            entry.line
+        })
+        .collect()
+}
+
+/// 磁盘上的后端日志文件，按时间先后排列；日志目录不存在时返回空列表
+pub(crate) fn list_log_files(app: &AppHandle) -> Result<Vec<LogFileInfo>, PortError> {
+    let Some(dir) = log_dir(app).filter(|dir| dir.is_dir()) else {
+        return Ok(Vec::new());
+    };
+    let files = log_file_names(&dir)
+        .map_err(log_file_failed)?
+        .into_iter()
+        .filter_map(|name| {
+            let metadata = fs::metadata(dir.join(&name)).ok()?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_millis() as u64);
+            Some(LogFileInfo {
+                workspace_id: log_file_workspace(&name).to_string(),
+                name,
+                size: metadata.len(),
+                modified,
+            })
+        })
+        .collect();
+    Ok(files)
+}
+
+/// 从 `offset` 字节处读取最多 `limit` 字节（不超过 1 MiB）；未读到文件末尾时在最后一个换行处截断，
+/// 保证每次返回完整的行。`file` 必须是 `list_log_files` 返回的文件名
+pub(crate) fn read_log_file(
+    app: &AppHandle,
+    file: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<LogChunk, PortError> {
+    let not_found = || PortError::LogFileNotFound {
+        name: file.to_string(),
+    };
+    // 只接受目录内的文件名，拒绝路径分隔符与 `..`
+    if !is_log_file_name(file) || file.contains(['/', '\\']) || file.contains("..") {
+        return Err(not_found());
+    }
+    let path = log_dir(app).ok_or_else(not_found)?.join(file);
+    let mut handle = File::open(&path).map_err(|_| not_found())?;
+    let size = handle.metadata().map_err(log_file_failed)?.len();
+
+    let offset = offset.min(size);
+    let limit = limit.min(MAX_READ_BYTES);
+    handle
+        .seek(SeekFrom::Start(offset))
+        .map_err(log_file_failed)?;
+    let mut bytes = Vec::with_capacity(limit);
+    handle
+        .take(limit as u64)
+        .read_to_end(&mut bytes)
+        .map_err(log_file_failed)?;
+
+    let eof = offset + bytes.len() as u64 >= size;
+    if !eof {
+        // 单行超过 `limit` 时按原样返回，避免调用方卡在同一位置
+        if let Some(newline) = bytes.iter().rposition(|byte| *byte == b'\n') {
+            bytes.truncate(newline + 1);
+        }
+    }
+    Ok(LogChunk {
+        content: String::from_utf8_lossy(&bytes).into_owned(),
+        next_offset: offset + bytes.len() as u64,
+        eof,
+    })
+}
@@ -0,0 +1,214 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::ShellExt;
+
+use crate::backend::{sidecar_failed, BACKEND_SIDECAR};
+use crate::health::fetch_version;
+use crate::ports::{run_tool_with_timeout, PortError};
+
+/// `--version` 检查的最长执行时间
+const VERSION_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+/// 与当前前端接口兼容的最低后端版本
+const MIN_BACKEND_VERSION: Version = Version {
+    major: 0,
+    minor: 1,
+    patch: 0,
+};
+/// 后端版本低于 [`MIN_BACKEND_VERSION`] 时发送，载荷为 [`BackendVersionInfo`]
+pub(crate) const BACKEND_INCOMPATIBLE_EVENT: &str = "backend://incompatible";
+/// Windows 上可以直接启动的扩展名
+#[cfg(target_os = "windows")]
+const RUNNABLE_EXTENSIONS: [&str; 4] = ["exe", "com", "bat", "cmd"];
+
+/// 用户指定的后端程序；`path` 为规范化后的绝对路径
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendBinaryInfo {
+    pub path: String,
+    pub size: u64,
+    /// `--version` 输出的第一行，未检查时为 `None`
+    pub version: Option<String>,
+}
+
+/// 展开开头的 `~` 并规范化路径，同时解析相对路径与符号链接；路径不存在时返回 `BackendNotFound`
+fn canonicalize(app: &AppHandle, path: &Path) -> Result<PathBuf, PortError> {
+    let not_found = || PortError::BackendNotFound {
+        path: path.to_string_lossy().into_owned(),
+    };
+    let expanded = match path.strip_prefix("~") {
+        Ok(rest) => app.path().home_dir().map_err(|_| not_found())?.join(rest),
+        Err(_) => path.to_path_buf(),
+    };
+    fs::canonicalize(expanded).map_err(|_| not_found())
+}
+
+#[cfg(unix)]
+fn is_executable(_path: &Path, metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(target_os = "windows")]
+fn is_executable(path: &Path, _metadata: &fs::Metadata) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            RUNNABLE_EXTENSIONS
+                .iter()
+                .any(|runnable| extension.eq_ignore_ascii_case(runnable))
+        })
+}
+
+/// 校验用户指定的后端程序：存在、是文件且可执行（Unix 检查执行权限，Windows 检查扩展名），
+/// 返回规范化后的路径。`check_version` 为 `true` 时再以 `--version` 运行一次并记录输出
+pub(crate) fn validate_backend_binary(
+    app: &AppHandle,
+    path: &Path,
+    check_version: bool,
+) -> Result<BackendBinaryInfo, PortError> {
+    let path = canonicalize(app, path)?;
+    let display = path.to_string_lossy().into_owned();
+    let metadata = fs::metadata(&path).map_err(|_| PortError::BackendNotFound {
+        path: display.clone(),
+    })?;
+    if !metadata.is_file() {
+        return Err(PortError::BackendNotFound { path: display });
+    }
+    if !is_executable(&path, &metadata) {
+        return Err(PortError::NotExecutable { path: display });
+    }
+
+    let version = if check_version {
+        Some(binary_version(&display)?)
+    } else {
+        None
+    };
+    Ok(BackendBinaryInfo {
+        path: display,
+        size: metadata.len(),
+        version,
+    })
+}
+
+fn binary_version(path: &str) -> Result<String, PortError> {
+    let failed = |reason: String| PortError::VersionCheckFailed {
+        path: path.to_string(),
+        reason,
+    };
+    let output = run_tool_with_timeout(path, &["--version"], VERSION_CHECK_TIMEOUT)
+        .map_err(|error| failed(error.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(format!(
+            "退出码 {}",
+            output.status.code().unwrap_or(-1)
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
+/// `主版本.次版本.修订号`，预发布与构建元数据不参与比较
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// 取输出中第一个形如 `1.2.3` 的版本号，允许前后有其他内容（`v` 前缀、程序名、`-beta` 后缀等）
+fn parse_version(text: &str) -> Option<Version> {
+    text.split(|c: char| !c.is_ascii_digit() && c != '.')
+        .find_map(|candidate| {
+            let mut parts = candidate.split('.').map(|part| part.parse::<u64>().ok());
+            let version = Version {
+                major: parts.next()??,
+                minor: parts.next()??,
+                patch: parts.next()??,
+            };
+            Some(version)
+        })
+}
+
+/// `get_backend_version` 的结果；无法从输出中解析出版本号时 `version` 为 `None`，视为不兼容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendVersionInfo {
+    pub version: Option<String>,
+    pub compatible: bool,
+    pub minimum: String,
+}
+
+/// 以 `--version` 运行后端程序并返回 stdout 与 stderr；`executable` 为 `None` 时使用打包的 sidecar
+async fn version_output(app: &AppHandle, executable: Option<&Path>) -> Result<String, PortError> {
+    let command = match executable {
+        Some(path) => {
+            let binary = validate_backend_binary(app, path, false)?;
+            app.shell().command(binary.path)
+        }
+        None => app
+            .shell()
+            .sidecar(BACKEND_SIDECAR)
+            .map_err(sidecar_failed)?,
+    };
+    let output = tokio::time::timeout(VERSION_CHECK_TIMEOUT, command.arg("--version").output())
+        .await
+        .map_err(|_| PortError::CommandTimedOut {
+            tool: BACKEND_SIDECAR.to_string(),
+            timeout_ms: VERSION_CHECK_TIMEOUT.as_millis() as u64,
+        })?
+        .map_err(sidecar_failed)?;
+    // 不认识 `--version` 的旧版本会以非 0 状态退出，但用法说明里通常仍带有版本号
+    Ok(format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+/// 获取后端版本并与 [`MIN_BACKEND_VERSION`] 比较：`running_port` 不为 `None` 时请求运行中后端的 `/version`，
+/// 否则以 `--version` 运行后端程序。不兼容时发送 `backend://incompatible` 事件
+pub(crate) async fn backend_version(
+    app: &AppHandle,
+    running_port: Option<u16>,
+    executable: Option<&Path>,
+) -> Result<BackendVersionInfo, PortError> {
+    let output = match running_port {
+        Some(port) => fetch_version(app, port, VERSION_CHECK_TIMEOUT).await?,
+        None => version_output(app, executable).await?,
+    };
+    let version = parse_version(&output);
+    let info = BackendVersionInfo {
+        version: version.map(|version| version.to_string()),
+        compatible: version.is_some_and(|version| version >= MIN_BACKEND_VERSION),
+        minimum: MIN_BACKEND_VERSION.to_string(),
+    };
+    if !info.compatible {
+        tracing::warn!(version = ?info.version, minimum = %info.minimum, "后端版本不兼容");
+        let _ = app.emit(BACKEND_INCOMPATIBLE_EVENT, info.clone());
+    }
+    Ok(info)
+}
+
+/// 启动后端前调用：版本不兼容时返回 `BackendIncompatible`，避免前端对着旧接口报出难以理解的错误
+pub(crate) async fn ensure_compatible(
+    app: &AppHandle,
+    executable: Option<&Path>,
+) -> Result<(), PortError> {
+    let info = backend_version(app, None, executable).await?;
+    if !info.compatible {
+        return Err(PortError::BackendIncompatible(info));
+    }
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use tauri::image::Image;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::ports::PortError;
+
+/// 单次写入或读取的数据上限（字节）；图片按 PNG 与解码后的像素数据分别计算
+pub(crate) const MAX_CLIPBOARD_BYTES: usize = 20 * 1024 * 1024;
+
+/// 剪贴板中的数据类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardKind {
+    Text,
+    /// 只能写入；系统剪贴板接口不提供 HTML 的读取
+    Html,
+    Image,
+}
+
+/// `clipboard_write` 写入的内容，前端通过 `kind` 字段区分
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ClipboardContent {
+    Text {
+        text: String,
+    },
+    /// 同时写入纯文本版本，供不支持 HTML 的程序粘贴；`altText` 为空时由 HTML 去掉标签得到
+    #[serde(rename_all = "camelCase")]
+    Html {
+        html: String,
+        alt_text: Option<String>,
+    },
+    /// base64 编码的 PNG
+    Image {
+        png: String,
+    },
+}
+
+/// 从剪贴板读取的图片，`png` 为 base64 编码
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardImage {
+    pub png: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// `clipboard_read` 的结果：`available` 列出剪贴板中现有的类别（图片只在请求时检查），只有请求的类别才附带数据
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClipboardData {
+    pub available: Vec<ClipboardKind>,
+    pub text: Option<String>,
+    pub image: Option<ClipboardImage>,
+}
+
+fn failed(error: impl ToString) -> PortError {
+    PortError::ClipboardFailed {
+        reason: error.to_string(),
+    }
+}
+
+fn ensure_size(size: usize) -> Result<(), PortError> {
+    if size > MAX_CLIPBOARD_BYTES {
+        return Err(PortError::ClipboardTooLarge {
+            size,
+            limit: MAX_CLIPBOARD_BYTES,
+        });
+    }
+    Ok(())
+}
+
+/// HTML 的纯文本版本：去掉标签与注释，块级元素换行，解码常见实体
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let tag = &rest[start..];
+        let end = if tag.starts_with("<!--") {
+            tag.find("-->").map(|end| end + 3)
+        } else {
+            tag.find('>').map(|end| end + 1)
+        };
+        let Some(end) = end else {
+            rest = "";
+            break;
+        };
+        let name = tag[1..end - 1]
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if matches!(
+            name.as_str(),
+            "br" | "p" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+        ) && !text.ends_with('\n')
+        {
+            text.push('\n');
+        }
+        rest = &tag[end..];
+    }
+    text.push_str(rest);
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+pub(crate) fn write(app: &AppHandle, content: ClipboardContent) -> Result<(), PortError> {
+    let clipboard = app.clipboard();
+    match content {
+        ClipboardContent::Text { text } => {
+            ensure_size(text.len())?;
+            clipboard.write_text(text).map_err(failed)
+        }
+        ClipboardContent::Html { html, alt_text } => {
+            ensure_size(html.len() + alt_text.as_ref().map_or(0, String::len))?;
+            let alt_text = alt_text.unwrap_or_else(|| html_to_text(&html));
+            clipboard.write_html(html, Some(alt_text)).map_err(failed)
+        }
+        ClipboardContent::Image { png } => {
+            // 先按 base64 长度估算，避免为超限的数据分配内存
+            ensure_size(png.len() / 4 * 3)?;
+            let bytes =
+                STANDARD
+                    .decode(png.as_bytes())
+                    .map_err(|e| PortError::InvalidClipboardData {
+                        reason: format!("图片不是有效的 base64: {e}"),
+                    })?;
+            let image = Image::from_bytes(&bytes).map_err(|e| PortError::InvalidClipboardData {
+                reason: format!("图片不是有效的 PNG: {e}"),
+            })?;
+            ensure_size(image.rgba().len())?;
+            clipboard.write_image(&image).map_err(failed)
+        }
+    }
+}
+
+fn encode_png(image: &Image<'_>) -> Result<Vec<u8>, PortError> {
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, image.width(), image.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(failed)?;
+    writer.write_image_data(image.rgba()).map_err(failed)?;
+    writer.finish().map_err(failed)?;
+    Ok(png)
+}
+
+/// 检查剪贴板中的文本与图片，只为 `kinds` 中的类别返回数据。读取图片需要复制整张位图，
+/// 只在请求图片时才读取，因此未请求时 `available` 不会包含图片
+pub(crate) fn read(app: &AppHandle, kinds: &[ClipboardKind]) -> Result<ClipboardData, PortError> {
+    if kinds.contains(&ClipboardKind::Html) {
+        return Err(failed("系统剪贴板接口不支持读取 HTML"));
+    }
+    let clipboard = app.clipboard();
+    let mut data = ClipboardData::default();
+
+    // 剪贴板中没有对应格式时读取返回错误，视为不存在
+    if let Some(text) = clipboard.read_text().ok().filter(|text| !text.is_empty()) {
+        data.available.push(ClipboardKind::Text);
+        if kinds.contains(&ClipboardKind::Text) {
+            ensure_size(text.len())?;
+            data.text = Some(text);
+        }
+    }
+    if !kinds.contains(&ClipboardKind::Image) {
+        return Ok(data);
+    }
+    if let Ok(image) = clipboard.read_image() {
+        data.available.push(ClipboardKind::Image);
+        ensure_size(image.rgba().len())?;
+        let png = encode_png(&image)?;
+        ensure_size(png.len())?;
+        data.image = Some(ClipboardImage {
+            png: STANDARD.encode(&png),
+            width: image.width(),
+            height: image.height(),
+        });
+    }
+    Ok(data)
+}
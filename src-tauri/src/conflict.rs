@@ -0,0 +1,121 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+use crate::backend::{running_backend, running_backends, DEFAULT_WORKSPACE};
+use crate::orphan::{cleanup_orphan, recorded_pid, OrphanOutcome};
+use crate::ports::{can_bind, pids_listening_on, PortError, Protocol, COMMON_DEV_PORTS};
+use crate::process::{describe_processes, ProcessInfo};
+use crate::{pick_free_port, save_port};
+
+/// 首选端口被占用时，按该跨度在其后查找空闲端口
+pub(crate) const CONFLICT_SEARCH_SPAN: u16 = 100;
+/// 启动时换用了其他端口时发送，载荷为 [`PortResolution`]，前端据此提示而不是报错
+pub(crate) const BACKEND_PORT_CHANGED_EVENT: &str = "backend://port-changed";
+
+/// `resolve_port_conflict` 的结果；`occupied_by` 为占用首选端口的进程，端口空闲或无法识别时为 `None`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortResolution {
+    pub port: u16,
+    pub changed: bool,
+    pub occupied_by: Option<ProcessInfo>,
+}
+
+fn unchanged(port: u16) -> PortResolution {
+    PortResolution {
+        port,
+        changed: false,
+        occupied_by: None,
+    }
+}
+
+/// 换端口时的查找范围：首选端口之后的 100 个端口，之后没有端口（首选端口为 65535）时改为其前的 100 个端口
+fn search_range(preferred: u16) -> (u16, u16) {
+    if preferred == u16::MAX {
+        (
+            preferred.saturating_sub(CONFLICT_SEARCH_SPAN),
+            preferred - 1,
+        )
+    } else {
+        (
+            preferred + 1,
+            preferred.saturating_add(CONFLICT_SEARCH_SPAN),
+        )
+    }
+}
+
+/// 为 `workspace` 的后端确定可用端口：`preferred` 空闲或正被该工作区的后端使用时原样返回；
+/// 被上次遗留的本应用后端占用时走遗留进程清理流程收回端口，绝不因此换端口；
+/// 被其他程序占用时在其后（首选端口为 65535 时在其前）100 个端口内另选一个（跳过常用开发端口），默认工作区还会把新端口写入设置
+pub(crate) fn resolve_conflict(
+    app: &AppHandle,
+    workspace: &str,
+    preferred: u16,
+) -> Result<PortResolution, PortError> {
+    if running_backend(app, workspace).is_some_and(|backend| backend.port == preferred) {
+        return Ok(unchanged(preferred));
+    }
+    let other_workspaces = running_backends(app)
+        .iter()
+        .any(|backend| backend.port == preferred);
+    if !other_workspaces && can_bind(preferred) {
+        return Ok(unchanged(preferred));
+    }
+
+    let listeners = pids_listening_on(preferred, Protocol::Tcp).unwrap_or_else(|error| {
+        warn!(port = preferred, %error, "无法确定占用端口的进程");
+        Vec::new()
+    });
+    if recorded_pid(app, workspace).is_some_and(|pid| listeners.contains(&pid)) {
+        let report = cleanup_orphan(app, workspace)?;
+        if matches!(report.outcome, OrphanOutcome::Killed) && report.port_freed {
+            info!(workspace, port = preferred, "已结束遗留后端并收回端口");
+            return Ok(unchanged(preferred));
+        }
+        // 身份不一致说明 PID 已被其他程序复用，按其他程序占用处理
+    }
+
+    let exclude: Vec<u16> = running_backends(app)
+        .iter()
+        .map(|backend| backend.port)
+        .chain(COMMON_DEV_PORTS.iter().copied())
+        .collect();
+    let (start, end) = search_range(preferred);
+    let port = pick_free_port(app, start, end, false, &exclude)?;
+    if workspace == DEFAULT_WORKSPACE {
+        save_port(app, port)?;
+    }
+    warn!(workspace, preferred, port, "端口被其他程序占用，改用新端口");
+    Ok(PortResolution {
+        port,
+        changed: true,
+        occupied_by: describe_processes(&listeners).into_iter().next(),
+    })
+}
+
+/// 启动流程使用：换用了新端口时发送 `backend://port-changed` 事件并返回新端口
+pub(crate) fn resolve_start_port(
+    app: &AppHandle,
+    workspace: &str,
+    preferred: u16,
+) -> Result<u16, PortError> {
+    let resolution = resolve_conflict(app, workspace, preferred)?;
+    if resolution.changed {
+        let _ = app.emit(BACKEND_PORT_CHANGED_EVENT, resolution.clone());
+    }
+    Ok(resolution.port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_range_never_includes_preferred_port() {
+        assert_eq!(search_range(8080), (8081, 8180));
+        assert_eq!(search_range(65500), (65501, u16::MAX));
+        assert_eq!(search_range(65534), (u16::MAX, u16::MAX));
+        assert_eq!(search_range(u16::MAX), (65435, 65534));
+    }
+}
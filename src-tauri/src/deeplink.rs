@@ -0,0 +1,276 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tracing::{info, warn};
+
+pub(crate) const DEEPLINK_SCHEME: &str = "openreview";
+/// 收到可识别的链接时发送给当前聚焦的窗口，载荷为 [`DeepLinkAction`]
+pub(crate) const DEEPLINK_ACTION_EVENT: &str = "deeplink://action";
+/// 链接格式错误或无法识别时发送，载荷为 [`DeepLinkError`]
+pub(crate) const DEEPLINK_ERROR_EVENT: &str = "deeplink://error";
+
+/// `openreview://` 链接对应的操作，序列化后前端可通过 `type` 字段区分
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum DeepLinkAction {
+    /// `openreview://forum?id=<forumId>[&noteId=<noteId>]`，与网页版的 `/forum` 地址一致
+    #[serde(rename_all = "camelCase")]
+    OpenSubmission {
+        forum_id: String,
+        note_id: Option<String>,
+    },
+    /// `openreview://settings`
+    OpenSettings,
+}
+
+/// `deeplink://error` 事件载荷
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeepLinkError {
+    pub url: String,
+    pub reason: String,
+}
+
+/// 冷启动时随命令行传入的链接；此时页面尚未加载，事件会丢失，由前端加载后通过 `take_pending_deep_links` 取走
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PendingDeepLinks {
+    pub actions: Vec<DeepLinkAction>,
+    pub errors: Vec<DeepLinkError>,
+}
+
+/// 冷启动链接，通过 `.manage()` 注册为全局状态
+#[derive(Default)]
+pub(crate) struct DeepLinks {
+    pending: Mutex<PendingDeepLinks>,
+}
+
+impl DeepLinks {
+    fn lock(&self) -> MutexGuard<'_, PendingDeepLinks> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn take(&self) -> PendingDeepLinks {
+        std::mem::take(&mut *self.lock())
+    }
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// 解码 `%XX`；`plus_as_space` 用于查询参数，`+` 表示空格。编码不完整或结果不是 UTF-8 时返回 `None`
+fn percent_decode(value: &str, plus_as_space: bool) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'%' => {
+                let high = hex_value(input.next()?)?;
+                let low = hex_value(input.next()?)?;
+                bytes.push(high << 4 | low);
+            }
+            b'+' if plus_as_space => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// OpenReview 的 forum / note ID 只包含字母、数字、`_` 与 `-`
+fn valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// 解析 `openreview://` 链接；scheme 与操作名不区分大小写，不认识的查询参数直接忽略
+pub(crate) fn parse(url: &str) -> Result<DeepLinkAction, String> {
+    let (scheme, rest) = url.split_once(':').ok_or("缺少 scheme")?;
+    if !scheme.eq_ignore_ascii_case(DEEPLINK_SCHEME) {
+        return Err(format!("不支持的 scheme: {scheme}"));
+    }
+    let rest = rest.trim_start_matches('/');
+    let rest = rest.split_once('#').map_or(rest, |(before, _)| before);
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+    let mut params = Vec::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = percent_decode(key, true).ok_or("查询参数编码无效")?;
+        let value = percent_decode(value, true).ok_or("查询参数编码无效")?;
+        params.push((key, value));
+    }
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    let path = percent_decode(path.trim_end_matches('/'), false).ok_or("路径编码无效")?;
+    match path.to_ascii_lowercase().as_str() {
+        "forum" => {
+            let forum_id = param("id").ok_or("缺少 id 参数")?;
+            if !valid_id(&forum_id) {
+                return Err(format!("无效的 forum ID: {forum_id}"));
+            }
+            let note_id = param("noteId").filter(|id| !id.is_empty());
+            if let Some(note_id) = note_id.as_deref().filter(|id| !valid_id(id)) {
+                return Err(format!("无效的 note ID: {note_id}"));
+            }
+            Ok(DeepLinkAction::OpenSubmission { forum_id, note_id })
+        }
+        "settings" => Ok(DeepLinkAction::OpenSettings),
+        "" => Err("缺少操作".to_string()),
+        other => Err(format!("未知的操作: {other}")),
+    }
+}
+
+/// 当前聚焦的窗口，没有时为主窗口
+fn target_window(app: &AppHandle) -> String {
+    app.webview_windows()
+        .into_iter()
+        .find(|(_, window)| window.is_focused().unwrap_or(false))
+        .map_or_else(|| "main".to_string(), |(label, _)| label)
+}
+
+/// 运行中收到链接：解析后发送给当前聚焦的窗口
+fn dispatch(app: &AppHandle, url: &str) {
+    let target = target_window(app);
+    match parse(url) {
+        Ok(action) => {
+            info!(url, ?action, "收到深度链接");
+            let _ = app.emit_to(target.as_str(), DEEPLINK_ACTION_EVENT, action);
+        }
+        Err(reason) => {
+            warn!(url, %reason, "无法识别的深度链接");
+            let error = DeepLinkError {
+                url: url.to_string(),
+                reason,
+            };
+            let _ = app.emit_to(target.as_str(), DEEPLINK_ERROR_EVENT, error);
+        }
+    }
+}
+
+/// 注册 `openreview://` 并开始处理链接。安装包已在系统中注册 scheme；
+/// Linux 与 Windows 上未经安装包运行（AppImage、开发构建）时在首次运行时补注册。
+/// 已运行时再次打开的链接经单实例插件转发到这里
+pub(crate) fn init(app: &AppHandle) {
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(error) = app.deep_link().register_all() {
+        warn!(%error, "无法注册 openreview:// 链接");
+    }
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        let state = app.state::<DeepLinks>();
+        let mut pending = state.lock();
+        for url in urls {
+            match parse(url.as_str()) {
+                Ok(action) => pending.actions.push(action),
+                Err(reason) => pending.errors.push(DeepLinkError {
+                    url: url.as_str().to_string(),
+                    reason,
+                }),
+            }
+        }
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            dispatch(&handle, url.as_str());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(forum_id: &str, note_id: Option<&str>) -> DeepLinkAction {
+        DeepLinkAction::OpenSubmission {
+            forum_id: forum_id.to_string(),
+            note_id: note_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn valid_links_are_parsed() {
+        assert_eq!(
+            parse("openreview://forum?id=abc123"),
+            Ok(submission("abc123", None))
+        );
+        assert_eq!(
+            parse("OpenReview://Forum/?id=abc_1-2&noteId=xyz&utm=mail#top"),
+            Ok(submission("abc_1-2", Some("xyz")))
+        );
+        assert_eq!(
+            parse("openreview://forum?id=abc&noteId="),
+            Ok(submission("abc", None))
+        );
+        assert_eq!(
+            parse("openreview://settings"),
+            Ok(DeepLinkAction::OpenSettings)
+        );
+        assert_eq!(
+            parse("openreview:settings/"),
+            Ok(DeepLinkAction::OpenSettings)
+        );
+    }
+
+    #[test]
+    fn wrong_scheme_is_rejected() {
+        assert_eq!(
+            parse("https://openreview.net/forum?id=abc"),
+            Err("不支持的 scheme: https".to_string())
+        );
+        assert_eq!(parse("openreview"), Err("缺少 scheme".to_string()));
+    }
+
+    #[test]
+    fn missing_or_unknown_path_is_rejected() {
+        assert_eq!(parse("openreview://"), Err("缺少操作".to_string()));
+        assert_eq!(parse("openreview://?id=abc"), Err("缺少操作".to_string()));
+        assert_eq!(
+            parse("openreview://profile"),
+            Err("未知的操作: profile".to_string())
+        );
+        assert_eq!(parse("openreview://forum"), Err("缺少 id 参数".to_string()));
+    }
+
+    #[test]
+    fn percent_encoded_params_are_decoded() {
+        assert_eq!(
+            parse("openreview://%66orum?%69d=abc%2D1&noteId=n%5F2"),
+            Ok(submission("abc-1", Some("n_2")))
+        );
+        // 解码后才校验 ID，编码过的非法字符同样被拒绝
+        assert_eq!(
+            parse("openreview://forum?id=a%20b"),
+            Err("无效的 forum ID: a b".to_string())
+        );
+        assert_eq!(
+            parse("openreview://forum?id=a+b"),
+            Err("无效的 forum ID: a b".to_string())
+        );
+        assert_eq!(
+            parse("openreview://forum?id=abc%2"),
+            Err("查询参数编码无效".to_string())
+        );
+        assert_eq!(
+            parse("openreview://forum%ZZ?id=abc"),
+            Err("路径编码无效".to_string())
+        );
+        assert_eq!(percent_decode("%E4%BD%A0", false).as_deref(), Some("你"));
+        assert_eq!(percent_decode("%FF", false), None);
+        assert_eq!(percent_decode("a+b", false).as_deref(), Some("a+b"));
+    }
+}
@@ -0,0 +1,116 @@
+use tauri::AppHandle;
+
+use crate::ports::PortError;
+
+/// 以管理员身份重新启动时附加的参数，值为旧实例的 PID；
+/// 新实例等旧实例退出后再初始化单实例插件，否则会被当作重复启动直接退出
+#[cfg(windows)]
+const WAIT_FOR_PID_ARG: &str = "--wait-for-pid";
+/// 等待旧实例退出的最长时间，旧实例退出前需要先停止后端
+#[cfg(windows)]
+const PREVIOUS_INSTANCE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// `ShellExecuteW` 返回值不大于 32 表示失败；用户在 UAC 对话框中选择“否”时为拒绝访问
+#[cfg(windows)]
+const SE_ERR_ACCESSDENIED: isize = 5;
+
+/// 当前进程的访问令牌是否已提升（以管理员身份运行）
+#[cfg(windows)]
+pub(crate) fn is_elevated() -> bool {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    let mut token = HANDLE::default();
+    // SAFETY: 只查询当前进程自身的令牌，句柄在返回前关闭
+    unsafe {
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut size = 0;
+        let queried = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(std::ptr::from_mut(&mut elevation).cast()),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        );
+        let _ = CloseHandle(token);
+        queried.is_ok() && elevation.TokenIsElevated != 0
+    }
+}
+
+/// 目前只有 Windows 需要提权才能结束其他用户的进程，其他平台视为已提升
+#[cfg(not(windows))]
+pub(crate) fn is_elevated() -> bool {
+    true
+}
+
+/// 按 Windows 命令行规则为参数加引号
+#[cfg(windows)]
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    format!("\"{}\"", arg.replace('"', "\\\""))
+}
+
+/// 以 `runas` 方式重新启动当前程序（弹出 UAC 确认），保留原有命令行参数，成功后退出当前实例
+#[cfg(windows)]
+pub(crate) fn relaunch_elevated(app: &AppHandle) -> Result<(), PortError> {
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let exe = std::env::current_exe().map_err(|error| PortError::ElevationFailed {
+        reason: error.to_string(),
+    })?;
+    let mut args: Vec<String> = std::env::args()
+        .skip(1)
+        .map(|arg| quote_arg(&arg))
+        .collect();
+    args.push(format!("{WAIT_FOR_PID_ARG}={}", std::process::id()));
+
+    // SAFETY: 所有字符串参数在调用期间有效
+    let instance = unsafe {
+        ShellExecuteW(
+            HWND::default(),
+            w!("runas"),
+            &HSTRING::from(exe.as_os_str()),
+            &HSTRING::from(args.join(" ")),
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    match instance.0 as isize {
+        SE_ERR_ACCESSDENIED => Err(PortError::ElevationCancelled),
+        code if code <= 32 => Err(PortError::ElevationFailed {
+            reason: format!("ShellExecuteW 返回 {code}"),
+        }),
+        _ => {
+            tracing::info!("已以管理员身份重新启动，退出当前实例");
+            app.exit(0);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub(crate) fn relaunch_elevated(_app: &AppHandle) -> Result<(), PortError> {
+    Err(PortError::ElevationUnsupported)
+}
+
+/// 由 `relaunch_elevated` 启动时，在构建应用前等待旧实例退出
+#[cfg(windows)]
+pub(crate) fn wait_for_previous_instance() {
+    let prefix = format!("{WAIT_FOR_PID_ARG}=");
+    let Some(pid) =
+        std::env::args().find_map(|arg| arg.strip_prefix(&prefix).and_then(|pid| pid.parse().ok()))
+    else {
+        return;
+    };
+    crate::process::wait_for_exit(&[pid], PREVIOUS_INSTANCE_TIMEOUT);
+}
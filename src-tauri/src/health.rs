@@ -0,0 +1,287 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::backend::running_backend;
+use crate::ports::PortError;
+
+pub(crate) const HEALTH_PATH: &str = "/health";
+const VERSION_PATH: &str = "/version";
+/// 未指定主机时访问的本机地址
+const DEFAULT_HOST: &str = "127.0.0.1";
+
+/// 复用连接池的 HTTP 客户端，通过 `.manage()` 注册为全局状态，避免每次轮询重新握手
+pub(crate) struct HealthClient(reqwest::Client);
+
+impl Default for HealthClient {
+    fn default() -> Self {
+        // 后端只在本机，不经过系统代理（企业环境的 HTTP_PROXY 会截走回环请求）
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .unwrap_or_default();
+        Self(client)
+    }
+}
+
+/// 健康检查结果；`status` / `version` 取自响应 JSON，缺失或响应不是 JSON 时为 `None`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub url: String,
+    pub http_status: u16,
+    pub status: Option<String>,
+    pub version: Option<String>,
+    pub latency_ms: u64,
+}
+
+/// 拼出后端地址 `http://{host}:{port}{path}`，`host` 缺省为 `127.0.0.1`，由调用方先用 `resolve_host` 校验；
+/// `path` 须为空或以 `/` 开头，且只包含可见 ASCII 字符
+/// （空格、控制字符与非 ASCII 字符需由调用方先做百分号编码）
+pub(crate) fn backend_url(host: Option<&str>, port: u16, path: &str) -> Result<String, PortError> {
+    let valid =
+        path.is_empty() || (path.starts_with('/') && path.chars().all(|c| c.is_ascii_graphic()));
+    if !valid {
+        return Err(PortError::InvalidUrlPath {
+            path: path.to_string(),
+        });
+    }
+    let host = match host.map(str::trim).filter(|host| !host.is_empty()) {
+        Some(host) => {
+            let bare = host.trim_start_matches('[').trim_end_matches(']');
+            if bare.parse::<std::net::Ipv6Addr>().is_ok() {
+                format!("[{bare}]")
+            } else {
+                bare.to_string()
+            }
+        }
+        None => DEFAULT_HOST.to_string(),
+    };
+    Ok(format!("http://{host}:{port}{path}"))
+}
+
+fn request_failed(url: &str, timeout: Duration, error: reqwest::Error) -> PortError {
+    if error.is_timeout() {
+        PortError::HealthTimedOut {
+            url: url.to_string(),
+            timeout_ms: timeout.as_millis() as u64,
+        }
+    } else if error.is_connect() {
+        PortError::ConnectionRefused {
+            url: url.to_string(),
+        }
+    } else {
+        PortError::Unhealthy {
+            url: url.to_string(),
+            reason: error.to_string(),
+        }
+    }
+}
+
+/// 请求 `http://{host}:{port}{path}`，2xx 即视为就绪；连接被拒绝、超时与非 2xx 响应都视为未就绪
+pub(crate) async fn probe_ready(
+    app: &AppHandle,
+    host: Option<&str>,
+    port: u16,
+    path: &str,
+    timeout: Duration,
+) -> Result<bool, PortError> {
+    let url = backend_url(host, port, path)?;
+    match fetch_health(client(app), url, timeout).await {
+        Ok(_) => Ok(true),
+        Err(
+            PortError::ConnectionRefused { .. }
+            | PortError::HealthTimedOut { .. }
+            | PortError::Unhealthy { .. },
+        ) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// 请求运行中后端的 `/version` 并返回响应正文，由调用方从中解析版本号
+pub(crate) async fn fetch_version(
+    app: &AppHandle,
+    port: u16,
+    timeout: Duration,
+) -> Result<String, PortError> {
+    let url = backend_url(None, port, VERSION_PATH)?;
+    let response = client(app)
+        .get(&url)
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| request_failed(&url, timeout, e))?;
+    if !response.status().is_success() {
+        return Err(PortError::Unhealthy {
+            reason: format!("HTTP {}", response.status().as_u16()),
+            url,
+        });
+    }
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| request_failed(&url, timeout, e))?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// 请求后端的 `/health`；未指定 `url` 时使用 Rust 侧为 `workspace` 启动的后端地址。
+/// 连接被拒绝、超时与响应异常分别对应不同的错误类型
+pub(crate) async fn check_health(
+    app: &AppHandle,
+    workspace: &str,
+    url: Option<String>,
+    timeout: Duration,
+) -> Result<HealthReport, PortError> {
+    let url = match url {
+        Some(url) => url,
+        None => {
+            let backend = running_backend(app, workspace).ok_or(PortError::BackendNotRunning)?;
+            backend_url(None, backend.port, HEALTH_PATH)?
+        }
+    };
+    fetch_health(client(app), url, timeout).await
+}
+
+fn client(app: &AppHandle) -> reqwest::Client {
+    app.state::<HealthClient>().0.clone()
+}
+
+async fn fetch_health(
+    client: reqwest::Client,
+    url: String,
+    timeout: Duration,
+) -> Result<HealthReport, PortError> {
+    let started = Instant::now();
+    let response = client
+        .get(&url)
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| request_failed(&url, timeout, e))?;
+    let http_status = response.status();
+    if !http_status.is_success() {
+        return Err(PortError::Unhealthy {
+            url,
+            reason: format!("HTTP {}", http_status.as_u16()),
+        });
+    }
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| request_failed(&url, timeout, e))?;
+    // 部分后端的健康检查只返回纯文本，2xx 即视为健康
+    let body: Value = serde_json::from_slice(&body).unwrap_or_default();
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let field = |key: &str| body.get(key).and_then(Value::as_str).map(str::to_string);
+    Ok(HealthReport {
+        status: field("status"),
+        version: field("version"),
+        http_status: http_status.as_u16(),
+        latency_ms,
+        url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// 只应答一次请求的 HTTP 服务器，返回其端口
+    fn serve_once(status: &'static str, body: &'static str) -> u16 {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            // 读完请求头再应答
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        });
+        port
+    }
+
+    fn fetch(port: u16) -> Result<HealthReport, PortError> {
+        let url = backend_url(None, port, HEALTH_PATH).unwrap();
+        let client = HealthClient::default().0;
+        tauri::async_runtime::block_on(fetch_health(client, url, TIMEOUT))
+    }
+
+    #[test]
+    fn ok_response_is_healthy() {
+        let port = serve_once("200 OK", r#"{"status":"ok","version":"1.2.3"}"#);
+        let report = fetch(port).unwrap();
+        assert_eq!(report.url, format!("http://127.0.0.1:{port}/health"));
+        assert_eq!(report.http_status, 200);
+        assert_eq!(report.status.as_deref(), Some("ok"));
+        assert_eq!(report.version.as_deref(), Some("1.2.3"));
+    }
+
+    #[test]
+    fn plain_text_ok_response_is_healthy() {
+        let report = fetch(serve_once("200 OK", "ok")).unwrap();
+        assert_eq!(report.http_status, 200);
+        assert_eq!(report.status, None);
+    }
+
+    #[test]
+    fn server_error_is_unhealthy() {
+        let port = serve_once("500 Internal Server Error", r#"{"status":"down"}"#);
+        match fetch(port) {
+            Err(PortError::Unhealthy { url, reason }) => {
+                assert_eq!(url, format!("http://127.0.0.1:{port}/health"));
+                assert_eq!(reason, "HTTP 500");
+            }
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn closed_port_is_refused() {
+        let port = TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        assert!(matches!(
+            fetch(port),
+            Err(PortError::ConnectionRefused { .. })
+        ));
+    }
+
+    #[test]
+    fn copied_and_opened_urls_share_one_format() {
+        assert_eq!(
+            backend_url(None, 5173, "").unwrap(),
+            "http://127.0.0.1:5173"
+        );
+        assert_eq!(
+            backend_url(None, 5173, "/").unwrap(),
+            format!("{}/", backend_url(None, 5173, "").unwrap())
+        );
+        // 空白 host 与未指定时相同
+        assert_eq!(
+            backend_url(Some("  "), 5173, "/docs?tab=1").unwrap(),
+            "http://127.0.0.1:5173/docs?tab=1"
+        );
+        assert_eq!(
+            backend_url(None, 5173, "/%E6%8A%A5%E5%91%8A").unwrap(),
+            "http://127.0.0.1:5173/%E6%8A%A5%E5%91%8A"
+        );
+    }
+}
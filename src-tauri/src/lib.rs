@@ -1,123 +1,1454 @@
-#[cfg(target_os = "windows")]
-use std::collections::HashSet;
-use std::net::TcpListener;
-use std::process::Command;
+#[cfg_attr(mobile, allow(dead_code))]
+mod attachments;
+mod autostart;
+mod backend;
+mod backend_log;
+mod binary;
+mod clipboard;
+mod conflict;
+mod deeplink;
+mod elevation;
+mod health;
+mod logging;
+mod metrics;
+mod notifications;
+mod orphan;
+mod ports;
+mod process;
+mod reservations;
+mod secondary_window;
+mod secrets;
+mod settings;
+mod shortcuts;
+#[cfg(desktop)]
+mod tray;
+mod waits;
+#[cfg_attr(mobile, allow(dead_code))]
+mod window_state;
 
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::{AppHandle, RunEvent, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_opener::OpenerExt;
+use tauri_plugin_store::StoreExt;
+
+use backend::{
+    backend_pids, backend_status, kill_on_exit, restart_with_last_config, running_backend,
+    running_backends, set_auto_restart_enabled, shutdown_backend, spawn_backend, start_and_wait,
+    stop_before_close, stop_before_exit, BackendConfig, BackendHandle, BackendState, BackendStatus,
+    StopOutcome, BACKEND_READY_TIMEOUT,
+};
+use backend_log::{list_log_files, read_log_file, BackendLog, LogChunk, LogFileInfo, LogLine};
+use binary::{
+    backend_version, ensure_compatible, validate_backend_binary, BackendBinaryInfo,
+    BackendVersionInfo,
+};
+use clipboard::{ClipboardContent, ClipboardData, ClipboardKind};
+use conflict::{resolve_conflict, resolve_start_port, PortResolution};
+use deeplink::{DeepLinks, PendingDeepLinks};
+use health::{backend_url, check_health, probe_ready, HealthClient, HealthReport, HEALTH_PATH};
+use metrics::{BackendMetrics, MetricsSample};
+use notifications::{Notifications, NotifyOptions};
+use orphan::{cleanup_orphan, OrphanReport};
+use ports::{
+    can_bind, can_connect_to, find_free_port_excluding, pids_listening_on, port_usage, probe_hosts,
+    probe_many, resolve_host, validate_port, AddressFamily, BindScope, PortError, PortState,
+    PortUsage, PortsKillFailure, Protocol, COMMON_DEV_PORTS, FIRST_UNPRIVILEGED_PORT,
+    WAIT_POLL_INTERVAL,
+};
+use process::{
+    describe_processes, ensure_killable, kill_confirmed, kill_port_listeners, kill_tree,
+    process_stats, scan_port_range, KillOptions, KillOutcome, KillSummary, PortEntry, PortStatus,
+    ProcStats, ProcessInfo,
+};
+use reservations::{reserve, PortReservations, DEFAULT_RESERVATION_TTL};
+use secondary_window::WindowInfo;
+use secrets::{SecretBackend, Secrets};
+use settings::{apply_patch, Settings, SettingsImport, SettingsReset};
+use shortcuts::ShortcutBinding;
+use waits::{wait_for_port_state, PortWaits};
+use window_state::WindowStates;
+
+pub(crate) const STORE_PATH: &str = "settings.json";
+/// 主窗口的 label，与 `tauri.conf.json` 一致
+pub(crate) const MAIN_WINDOW: &str = "main";
+/// `find_free_port` 上次成功的端口，属于内部状态而非设置
+const LAST_FREE_PORT_KEY: &str = "last_free_port";
+/// 设置中后端端口的默认值
+pub(crate) const DEFAULT_BACKEND_PORT: u16 = 5000;
+/// 请求进程退出后等待其自行结束的默认时长
+pub(crate) const DEFAULT_KILL_GRACE_MS: u64 = 5000;
+
+/// 在阻塞线程池中执行端口探测与外部命令，避免阻塞 IPC 线程导致界面卡顿
+pub(crate) async fn run_blocking<T, F>(task: F) -> Result<T, PortError>
+where
+    F: FnOnce() -> Result<T, PortError> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| PortError::TaskFailed {
+            source: e.to_string(),
+        })?
+}
+
+/// 应用版本号，构建时能取得 git 提交时附加为 semver 构建元数据，例如 `0.1.0+1a2b3c4`
 #[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+#[tracing::instrument]
+fn app_version() -> String {
+    match option_env!("GIT_COMMIT_HASH") {
+        Some(hash) => format!("{}+{hash}", env!("CARGO_PKG_VERSION")),
+        None => env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
+/// 按协议与地址族探测端口，供各个端口检查命令共用
+fn probe_usage(
+    port: u16,
+    host: Option<&str>,
+    protocol: Option<Protocol>,
+    interface: Option<BindScope>,
+    family: Option<AddressFamily>,
+) -> Result<PortUsage, PortError> {
+    validate_port(port)?;
+    let hosts = probe_hosts(host, interface, family.unwrap_or_default())?;
+    Ok(port_usage(port, &hosts, protocol.unwrap_or_default()))
 }
 
+/// 统一的端口占用检查：`protocol` 缺省为 TCP，`family` 缺省同时检查 IPv4 与 IPv6 的回环与通配地址。
+/// `is_port_in_use` 与 `udp_port_in_use` 为兼容已有调用而保留
 #[tauri::command]
-fn is_port_in_use(port: u16) -> bool {
-    TcpListener::bind(("127.0.0.1", port)).is_err()
+#[tracing::instrument]
+async fn port_in_use(
+    port: u16,
+    protocol: Option<Protocol>,
+    family: Option<AddressFamily>,
+) -> Result<bool, PortError> {
+    run_blocking(move || Ok(probe_usage(port, None, protocol, None, family)?.in_use)).await
 }
 
+/// 保持原有的布尔返回值，需要区分地址族时使用 `check_port`；`protocol` 缺省为 TCP，
+/// `interface` 缺省同时探测回环与通配地址
 #[tauri::command]
-fn force_kill_process_on_port(port: u16) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        let output = Command::new("netstat")
-            .args(["-ano", "-p", "tcp"])
-            .output()
-            .map_err(|e| format!("执行 netstat 失败: {e}"))?;
-        if !output.status.success() {
-            return Err(format!(
-                "netstat 返回非 0 状态码: {}",
-                output.status.code().unwrap_or(-1)
-            ));
-        }
+#[tracing::instrument]
+async fn is_port_in_use(
+    port: u16,
+    host: Option<String>,
+    protocol: Option<Protocol>,
+    interface: Option<BindScope>,
+    family: Option<AddressFamily>,
+) -> Result<bool, PortError> {
+    run_blocking(move || {
+        Ok(probe_usage(port, host.as_deref(), protocol, interface, family)?.in_use)
+    })
+    .await
+}
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let target = format!(":{port}");
-        let mut pids: HashSet<String> = HashSet::new();
+/// 以建立连接的方式检查 `host:port` 是否有服务在接受连接；绑定探测无法区分“已有进程监听”与
+/// “被防火墙或权限拦截”，局域网模式下也需要检查其他主机。`timeout_ms` 只限制连接，不含主机名解析；
+/// 超时或主机名无法解析都视为无法连接，端口 0 返回 `InvalidPort`
+#[tauri::command]
+#[tracing::instrument]
+async fn can_connect(host: String, port: u16, timeout_ms: u64) -> Result<bool, PortError> {
+    validate_port(port)?;
+    Ok(can_connect_to(&host, port, Duration::from_millis(timeout_ms)).await)
+}
 
-        for line in stdout.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
+/// `port_in_use` 的 UDP 简写，供只关心后端发现端口的调用方使用
+#[tauri::command]
+#[tracing::instrument]
+async fn udp_port_in_use(port: u16) -> Result<bool, PortError> {
+    port_in_use(port, Some(Protocol::Udp), None).await
+}
 
-            let columns: Vec<&str> = line.split_whitespace().collect();
-            if columns.len() < 5 {
-                continue;
-            }
+/// 批量版 `is_port_in_use`，返回值与 `ports` 一一对应
+#[tauri::command]
+#[tracing::instrument]
+async fn ports_in_use(ports: Vec<u16>) -> Result<Vec<bool>, PortError> {
+    ports.iter().try_for_each(|port| validate_port(*port))?;
+    run_blocking(move || Ok(probe_many(&ports))).await
+}
 
-            let local_addr = columns[1];
-            let state = columns[3];
-            let pid = columns[4];
+#[tauri::command]
+#[tracing::instrument]
+async fn check_port(
+    port: u16,
+    host: Option<String>,
+    protocol: Option<Protocol>,
+    interface: Option<BindScope>,
+    family: Option<AddressFamily>,
+) -> Result<PortUsage, PortError> {
+    run_blocking(move || probe_usage(port, host.as_deref(), protocol, interface, family)).await
+}
 
-            if state.eq_ignore_ascii_case("LISTENING")
-                && (local_addr.ends_with(&target) || local_addr.contains(&target))
-            {
-                pids.insert(pid.to_string());
-            }
-        }
+/// 诊断用：按默认方式（TCP，IPv4 与 IPv6 的回环与通配地址）探测端口，
+/// `checked` 列出探测过的每个地址，与 `occupied` 对照即可看出冲突出现在哪个地址上
+#[tauri::command]
+#[tracing::instrument]
+async fn probe_port(port: u16) -> Result<PortUsage, PortError> {
+    run_blocking(move || probe_usage(port, None, None, None, None)).await
+}
 
-        for pid in pids {
-            let status = Command::new("taskkill")
-                .args(["/PID", &pid, "/F"])
-                .status()
-                .map_err(|e| format!("执行 taskkill 失败 (PID={pid}): {e}"))?;
-            if !status.success() {
-                return Err(format!(
-                    "taskkill 返回非 0 状态码 (PID={pid}): {}",
-                    status.code().unwrap_or(-1)
-                ));
-            }
+/// 在 Rust 侧轮询端口状态，替代前端定时调用 `is_port_in_use`；
+/// 超时或被 `cancel_wait` 取消时返回 `false`，未指定 `interval_ms` 时使用默认轮询间隔。
+/// `host` 可以是 IP 地址或主机名（如 `localhost`），未指定时探测本机回环地址
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn wait_for_port(
+    app: AppHandle,
+    port: u16,
+    state: PortState,
+    timeout_ms: u64,
+    interval_ms: Option<u64>,
+    host: Option<String>,
+) -> Result<bool, PortError> {
+    validate_port(port)?;
+    let hosts = match host.filter(|host| !host.trim().is_empty()) {
+        Some(host) => Some(run_blocking(move || resolve_host(&host)).await?),
+        None => None,
+    };
+    let interval = interval_ms
+        .map(Duration::from_millis)
+        .unwrap_or(WAIT_POLL_INTERVAL);
+    let timeout = Duration::from_millis(timeout_ms);
+    Ok(wait_for_port_state(&app, port, hosts.as_deref(), state, timeout, interval).await)
+}
+
+/// 中断正在进行的 `wait_for_port`（`port` 为 `None` 时中断全部），返回被中断的数量；
+/// 之后发起的等待不受影响
+#[tauri::command]
+#[tracing::instrument(skip(waits))]
+fn cancel_wait(waits: State<'_, PortWaits>, port: Option<u16>) -> usize {
+    waits.cancel(port)
+}
+
+/// 在 `[start, end]` 内查找空闲端口；`avoid_common_ports` 为 `true` 时跳过 3000、8080 等常用开发端口
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn find_free_port(
+    app: AppHandle,
+    start: u16,
+    end: u16,
+    allow_privileged: Option<bool>,
+    avoid_common_ports: Option<bool>,
+) -> Result<u16, PortError> {
+    run_blocking(move || {
+        // 已启动但尚未开始监听的后端仍然可以绑定，需要显式跳过
+        let mut exclude: Vec<u16> = running_backends(&app)
+            .iter()
+            .map(|backend| backend.port)
+            .collect();
+        if avoid_common_ports.unwrap_or(false) {
+            exclude.extend_from_slice(COMMON_DEV_PORTS);
         }
+        pick_free_port(
+            &app,
+            start,
+            end,
+            allow_privileged.unwrap_or(false),
+            &exclude,
+        )
+    })
+    .await
+}
 
-        return Ok(());
+/// 在 `[start, end]` 内选择一个空闲端口，跳过 `exclude` 中的端口，并记住本次结果
+pub(crate) fn pick_free_port(
+    app: &AppHandle,
+    start: u16,
+    end: u16,
+    allow_privileged: bool,
+    exclude: &[u16],
+) -> Result<u16, PortError> {
+    let low = if allow_privileged {
+        start.max(1)
+    } else {
+        start.max(FIRST_UNPRIVILEGED_PORT)
+    };
+    if low > end {
+        return Err(PortError::InvalidRange { start, end });
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let output = Command::new("lsof")
-            .args(["-ti", &format!("tcp:{port}")])
-            .output()
-            .map_err(|e| format!("执行 lsof 失败: {e}"))?;
+    let store = app.store(STORE_PATH).ok();
+    let last_port = store
+        .as_ref()
+        .and_then(|store| store.get(LAST_FREE_PORT_KEY))
+        .and_then(|value| value.as_u64())
+        .and_then(|value| u16::try_from(value).ok())
+        .filter(|port| (low..=end).contains(port) && !exclude.contains(port));
 
-        if !output.status.success() && output.stdout.is_empty() {
-            return Ok(());
-        }
+    // 优先复用上次成功的端口，其余按顺序扫描
+    let port = last_port
+        .filter(|port| can_bind(*port))
+        .or_else(|| find_free_port_excluding(low, end, exclude))
+        .ok_or(PortError::NoFreePort { start: low, end })?;
 
-        if !output.status.success() {
-            return Err(format!(
-                "lsof 返回非 0 状态码: {}",
-                output.status.code().unwrap_or(-1)
-            ));
-        }
+    if let Some(store) = &store {
+        store.set(LAST_FREE_PORT_KEY, port);
+        let _ = store.save();
+    }
+    Ok(port)
+}
+
+pub(crate) fn store_failed(error: tauri_plugin_store::Error) -> PortError {
+    PortError::StoreFailed {
+        source: error.to_string(),
+    }
+}
+
+pub(crate) fn save_port(app: &AppHandle, port: u16) -> Result<(), PortError> {
+    let current = settings::load(app);
+    let updated = Settings {
+        backend_port: port,
+        ..current.clone()
+    };
+    settings::replace(app, &current, &updated).map(drop)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn get_saved_port(app: AppHandle) -> u16 {
+    settings::load(&app).backend_port
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn set_saved_port(app: AppHandle, port: u16) -> Result<(), PortError> {
+    validate_port(port)?;
+    save_port(&app, port)
+}
+
+/// 保存启动后端时注入的环境变量；取值变化时运行中的后端在状态中标记为需要重启
+#[tauri::command]
+#[tracing::instrument(skip(app, env))]
+fn set_backend_env(app: AppHandle, env: HashMap<String, String>) -> Result<(), PortError> {
+    let current = settings::load(&app);
+    let updated = Settings {
+        backend_env: env,
+        ..current.clone()
+    };
+    settings::replace(&app, &current, &updated).map(drop)
+}
+
+/// 读取设置；存储损坏或缺失时返回默认值
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn get_settings(app: AppHandle) -> Settings {
+    settings::load(&app)
+}
+
+/// 按 JSON Merge Patch 合并 `patch`（`null` 表示恢复默认值），校验通过后整体保存并返回新设置；
+/// 含未知字段或取值无效时返回 `InvalidSettings`，不做任何修改。有变化时向所有窗口发送一次
+/// `settings://changed`；后端环境变量或代理变化时运行中的后端标记为需要重启
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn update_settings(app: AppHandle, patch: Value) -> Result<Settings, PortError> {
+    let current = settings::load(&app);
+    let updated = apply_patch(&current, patch)?;
+    settings::replace(&app, &current, &updated)?;
+    Ok(updated)
+}
+
+/// 把 `scope` 中的设置项（camelCase 键名，如 `backendPort`）恢复默认值，未指定时恢复全部设置。
+/// 修改前先把整个设置存储备份到应用数据目录并返回备份路径；与 `update_settings` 一样发送
+/// `settings://changed`，并在需要时把运行中的后端标记为需要重启
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn reset_settings(app: AppHandle, scope: Option<Vec<String>>) -> Result<SettingsReset, PortError> {
+    settings::reset(&app, scope)
+}
+
+/// 从 `reset_settings` 返回的备份文件恢复设置；校验失败时返回 `InvalidSettings` 且不做任何修改
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn restore_settings_backup(app: AppHandle, path: String) -> Result<SettingsImport, PortError> {
+    settings::restore_backup(&app, Path::new(&path))
+}
+
+/// 把当前设置导出为 JSON 文件（不含 `*_KEY` / `*_TOKEN` 等敏感环境变量与代理凭据），
+/// 未指定 `path` 时弹出保存对话框；返回写入的路径，用户取消时返回 `None`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn export_settings(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<Option<String>, PortError> {
+    run_blocking(move || {
+        let Some(path) = path
+            .map(PathBuf::from)
+            .or_else(|| settings::pick_export_path(&app))
+        else {
+            return Ok(None);
+        };
+        settings::export(&app, &path)?;
+        Ok(Some(path.to_string_lossy().into_owned()))
+    })
+    .await
+}
+
+/// 从 `export_settings` 导出的文件（或旧版本的 `settings.json`）导入设置，未指定 `path` 时弹出打开对话框；
+/// 旧版本的文件先经过与启动时相同的迁移。校验失败时不修改任何设置；成功后发送 `settings://changed`，
+/// 用户取消时返回 `None`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn import_settings(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<Option<SettingsImport>, PortError> {
+    run_blocking(move || {
+        let Some(path) = path
+            .map(PathBuf::from)
+            .or_else(|| settings::pick_import_path(&app))
+        else {
+            return Ok(None);
+        };
+        settings::import(&app, &path).map(Some)
+    })
+    .await
+}
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        for pid in stdout.lines().map(str::trim).filter(|pid| !pid.is_empty()) {
-            let status = Command::new("kill")
-                .args(["-9", pid])
-                .status()
-                .map_err(|e| format!("执行 kill 失败 (PID={pid}): {e}"))?;
-            if !status.success() {
-                return Err(format!(
-                    "kill 返回非 0 状态码 (PID={pid}): {}",
-                    status.code().unwrap_or(-1)
-                ));
+/// 把密钥保存到系统钥匙串（macOS 钥匙串、Windows 凭据管理器、Linux Secret Service），
+/// 以应用标识区分命名空间；Linux 上没有 Secret Service 时保存在加密文件中
+#[tauri::command]
+#[tracing::instrument(skip(app, value))]
+async fn set_secret(app: AppHandle, key: String, value: String) -> Result<(), PortError> {
+    run_blocking(move || secrets::set(&app, &key, &value)).await
+}
+
+/// 读取密钥，不存在时返回 `None`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn get_secret(app: AppHandle, key: String) -> Result<Option<String>, PortError> {
+    run_blocking(move || secrets::get(&app, &key)).await
+}
+
+/// 删除密钥，不存在时视为成功
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn delete_secret(app: AppHandle, key: String) -> Result<(), PortError> {
+    run_blocking(move || secrets::delete(&app, &key)).await
+}
+
+/// 当前保存密钥的位置，可在设置界面提示 Linux 上正在使用加密文件
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn get_secret_backend(app: AppHandle) -> Result<SecretBackend, PortError> {
+    run_blocking(move || Ok(secrets::backend(&app))).await
+}
+
+/// 校验一份完整的设置（缺失的字段取默认值）而不保存，返回补全后的结果
+#[tauri::command]
+#[tracing::instrument]
+fn validate_settings(candidate: Value) -> Result<Settings, PortError> {
+    Settings::parse(candidate)
+}
+
+/// 查找端口上的监听进程并补充进程详情
+fn processes_on_port(port: u16) -> Result<Vec<ProcessInfo>, PortError> {
+    validate_port(port)?;
+    Ok(describe_processes(&pids_listening_on(port, Protocol::Tcp)?))
+}
+
+/// 端口上监听进程的 CPU 与内存占用，没有进程监听时返回 `None`；多个进程监听时取第一个
+#[tauri::command]
+#[tracing::instrument]
+async fn process_stats_on_port(port: u16) -> Result<Option<ProcStats>, PortError> {
+    validate_port(port)?;
+    run_blocking(move || {
+        let pids = pids_listening_on(port, Protocol::Tcp)?;
+        Ok(pids.first().and_then(|pid| process_stats(*pid)))
+    })
+    .await
+}
+
+#[tauri::command]
+#[tracing::instrument]
+async fn get_processes_on_port(port: u16) -> Result<Vec<ProcessInfo>, PortError> {
+    run_blocking(move || processes_on_port(port)).await
+}
+
+/// 诊断面板使用的别名，与 `get_processes_on_port` 共用同一查找逻辑；无监听时返回空列表
+#[tauri::command]
+#[tracing::instrument]
+async fn list_processes_on_port(port: u16) -> Result<Vec<ProcessInfo>, PortError> {
+    run_blocking(move || processes_on_port(port)).await
+}
+
+/// 后端启动失败时展示候选端口的占用情况
+#[tauri::command]
+#[tracing::instrument]
+async fn scan_ports(start: u16, end: u16) -> Result<Vec<PortStatus>, PortError> {
+    validate_port(start)?;
+    run_blocking(move || scan_port_range(start, end)).await
+}
+
+/// 诊断面板使用：列出本机所有监听中的 TCP 端口及其进程
+#[tauri::command]
+#[tracing::instrument]
+async fn listening_ports() -> Result<Vec<PortEntry>, PortError> {
+    run_blocking(process::listening_ports).await
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn kill_process_tree(
+    app: AppHandle,
+    pid: u32,
+    force: bool,
+    grace_ms: Option<u64>,
+) -> Result<Vec<u32>, PortError> {
+    let grace = Duration::from_millis(grace_ms.unwrap_or(DEFAULT_KILL_GRACE_MS));
+    let backends = backend_pids(&app);
+    run_blocking(move || {
+        ensure_killable(pid, &backends)?;
+        kill_tree(pid, force, grace)
+    })
+    .await
+}
+
+/// 两步确认流程的第二步：只结束界面上展示过的那个 PID
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn kill_pid(
+    app: AppHandle,
+    pid: u32,
+    force: bool,
+    expected_name: Option<String>,
+    grace_ms: Option<u64>,
+) -> Result<KillOutcome, PortError> {
+    let grace = Duration::from_millis(grace_ms.unwrap_or(DEFAULT_KILL_GRACE_MS));
+    let backends = backend_pids(&app);
+    run_blocking(move || {
+        ensure_killable(pid, &backends)?;
+        kill_confirmed(pid, force, expected_name.as_deref(), grace)
+    })
+    .await
+}
+
+/// 先请求进程正常退出，超过 `grace_ms` 仍存活的进程再强制结束；
+/// 默认跳过应用自身、其父进程及应用启动的后端，确需结束时传入 `allow_self`；
+/// `recursive` 与 `include_children` 等价，任一为 `true` 即连同子进程一起结束；
+/// `expected_names` 限定可结束的可执行文件名，不符的进程在 `skipped` 中返回供界面确认；
+/// `protocol` 为 `udp` 时结束绑定该 UDP 端口的进程；
+/// `dry_run` 为 `true` 时只在 `targets` 中返回将被结束的进程，供界面确认
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+// 每个选项都是前端可省略的独立参数
+#[allow(clippy::too_many_arguments)]
+async fn force_kill_process_on_port(
+    app: AppHandle,
+    port: u16,
+    grace_ms: Option<u64>,
+    allow_self: Option<bool>,
+    include_children: Option<bool>,
+    recursive: Option<bool>,
+    expected_names: Option<Vec<String>>,
+    protocol: Option<Protocol>,
+    dry_run: Option<bool>,
+) -> Result<KillSummary, PortError> {
+    validate_port(port)?;
+    let options = KillOptions {
+        grace: Duration::from_millis(grace_ms.unwrap_or(DEFAULT_KILL_GRACE_MS)),
+        allow_self: allow_self.unwrap_or(false),
+        backends: backend_pids(&app),
+        include_children: include_children.unwrap_or(false) || recursive.unwrap_or(false),
+        expected_names,
+        protocol: protocol.unwrap_or_default(),
+        dry_run: dry_run.unwrap_or(false),
+    };
+    run_blocking(move || kill_port_listeners(port, &options)).await
+}
+
+/// 同时结束多个端口上的监听进程（各端口并行，超过 `grace_ms` 仍存活的进程强制结束），返回各端口已结束的 PID。
+/// 某个端口失败时其余端口照常处理，最后以 `PartialKillFailure` 一并返回成功与失败的端口
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn force_kill_ports(
+    app: AppHandle,
+    ports: Vec<u16>,
+    grace_ms: u64,
+) -> Result<HashMap<u16, Vec<u32>>, PortError> {
+    let options = KillOptions {
+        grace: Duration::from_millis(grace_ms),
+        allow_self: false,
+        backends: backend_pids(&app),
+        include_children: false,
+        expected_names: None,
+        protocol: Protocol::Tcp,
+        dry_run: false,
+    };
+    run_blocking(move || kill_ports(ports, &options)).await
+}
+
+/// `force_kill_ports` 的主体：去重后并行处理各端口，汇总成功与失败的端口
+fn kill_ports(
+    mut ports: Vec<u16>,
+    options: &KillOptions,
+) -> Result<HashMap<u16, Vec<u32>>, PortError> {
+    ports.sort_unstable();
+    ports.dedup();
+    let results = std::thread::scope(|scope| {
+        let handles: Vec<_> = ports
+            .iter()
+            .map(|&port| {
+                scope.spawn(move || {
+                    validate_port(port)?;
+                    kill_port_listeners(port, options)
+                })
+            })
+            .collect();
+        ports
+            .iter()
+            .zip(handles)
+            .map(|(&port, handle)| {
+                let result = handle.join().unwrap_or_else(|_| {
+                    Err(PortError::TaskFailed {
+                        source: "结束进程的线程异常退出".to_string(),
+                    })
+                });
+                (port, result)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut killed = HashMap::new();
+    let mut errors = HashMap::new();
+    for (port, result) in results {
+        match result {
+            Ok(summary) => {
+                killed.insert(
+                    port,
+                    summary.killed.iter().map(|process| process.pid).collect(),
+                );
+            }
+            Err(error) => {
+                errors.insert(port, error);
             }
         }
+    }
+    if errors.is_empty() {
+        Ok(killed)
+    } else {
+        Err(PortError::PartialKillFailure(PortsKillFailure {
+            killed,
+            errors,
+        }))
+    }
+}
+
+/// 预留端口直到 `release_port` 或 `ttl_ms` 到期，避免检查与启动之间端口被抢占
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn reserve_port(app: AppHandle, port: u16, ttl_ms: Option<u64>) -> Result<u32, PortError> {
+    validate_port(port)?;
+    let ttl = ttl_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_RESERVATION_TTL);
+    reserve(&app, port, ttl)
+}
+
+/// 在启动后端前调用，释放预留的端口并返回端口号
+#[tauri::command]
+#[tracing::instrument(skip(reservations))]
+fn release_port(
+    reservations: State<'_, PortReservations>,
+    reservation_id: u32,
+) -> Result<u16, PortError> {
+    reservations.release(reservation_id)
+}
+
+/// 以 sidecar 方式启动工作区的后端（`workspace_id` 缺省为 `default`）；已在运行时返回现有句柄。
+/// 启动前检查后端版本，不兼容时返回 `BackendIncompatible`；指定的端口被其他程序占用时改用新端口并发送
+/// `backend://port-changed` 事件。进程退出时发送 `backend-exited` 事件
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn start_backend(
+    app: AppHandle,
+    config: BackendConfig,
+    workspace_id: Option<String>,
+) -> Result<BackendHandle, PortError> {
+    let workspace = backend::workspace_id(workspace_id)?;
+    let config = prepare_start(&app, &workspace, config).await?;
+    run_blocking(move || spawn_backend(&app, &workspace, &config)).await
+}
+
+/// 工作区尚无运行中的后端时：检查版本兼容性，并为指定的端口解决占用冲突
+async fn prepare_start(
+    app: &AppHandle,
+    workspace: &str,
+    mut config: BackendConfig,
+) -> Result<BackendConfig, PortError> {
+    if running_backend(app, workspace).is_some() {
+        return Ok(config);
+    }
+    ensure_compatible(app, config.executable.as_deref()).await?;
+    if let Some(port) = config.port {
+        let (app, workspace) = (app.clone(), workspace.to_string());
+        let port = run_blocking(move || resolve_start_port(&app, &workspace, port)).await?;
+        config.port = Some(port);
+    }
+    Ok(config)
+}
+
+/// 启动后端并等待其可用（端口接受连接且 `health_path`，缺省为 `/health`，返回 2xx）后才返回，
+/// 期间发送 `backend://startup` 进度事件；超时（缺省 30 秒）或启动中退出时返回附带 stderr 的错误
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn start_backend_and_wait(
+    app: AppHandle,
+    config: BackendConfig,
+    health_path: Option<String>,
+    timeout_ms: Option<u64>,
+    workspace_id: Option<String>,
+) -> Result<BackendHandle, PortError> {
+    let workspace = backend::workspace_id(workspace_id)?;
+    let config = prepare_start(&app, &workspace, config).await?;
+    let timeout = timeout_ms.map_or(BACKEND_READY_TIMEOUT, Duration::from_millis);
+    let health_path = health_path.unwrap_or_else(|| HEALTH_PATH.to_string());
+    start_and_wait(app, workspace, config, health_path, timeout).await
+}
+
+/// 停止工作区的后端并返回停止方式；没有运行中的后端时返回 `notRunning`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn stop_backend(
+    app: AppHandle,
+    timeout_ms: Option<u64>,
+    workspace_id: Option<String>,
+) -> Result<StopOutcome, PortError> {
+    let workspace = backend::workspace_id(workspace_id)?;
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_KILL_GRACE_MS));
+    run_blocking(move || shutdown_backend(&app, &workspace, timeout)).await
+}
+
+/// 停止后端并结束仍占用端口的进程，再用上次的配置（`port` 可覆盖端口）重新启动，
+/// 新进程开始接受连接后才返回；期间依次发送 `backend://restarting` 与 `backend://ready` 事件
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn restart_backend(
+    app: AppHandle,
+    port: Option<u16>,
+    timeout_ms: Option<u64>,
+    workspace_id: Option<String>,
+) -> Result<BackendHandle, PortError> {
+    let workspace = backend::workspace_id(workspace_id)?;
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_KILL_GRACE_MS));
+    restart_with_last_config(app, workspace, port, timeout).await
+}
+
+/// 校验用户指定的后端程序路径（支持 `~` 与相对路径），`check_version` 为 `true` 时以 `--version` 运行一次
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn validate_backend_path(
+    app: AppHandle,
+    path: String,
+    check_version: Option<bool>,
+) -> Result<BackendBinaryInfo, PortError> {
+    run_blocking(move || {
+        validate_backend_binary(&app, Path::new(&path), check_version.unwrap_or(false))
+    })
+    .await
+}
+
+/// 首选端口被其他程序占用时在其后另选空闲端口（默认工作区同时写入设置），返回最终端口与占用者；
+/// 占用者是上次遗留的本应用后端时结束它并沿用原端口
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn resolve_port_conflict(
+    app: AppHandle,
+    preferred: u16,
+    workspace_id: Option<String>,
+) -> Result<PortResolution, PortError> {
+    validate_port(preferred)?;
+    let workspace = backend::workspace_id(workspace_id)?;
+    run_blocking(move || resolve_conflict(&app, &workspace, preferred)).await
+}
+
+/// 后端版本及是否满足最低版本要求：工作区的后端在运行时请求其 `/version`，否则以 `--version`
+/// 运行 `executable`（缺省为打包的 sidecar）。不兼容时另外发送 `backend://incompatible` 事件
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn get_backend_version(
+    app: AppHandle,
+    executable: Option<String>,
+    workspace_id: Option<String>,
+) -> Result<BackendVersionInfo, PortError> {
+    let workspace = backend::workspace_id(workspace_id)?;
+    let port = running_backend(&app, &workspace).map(|backend| backend.port);
+    backend_version(&app, port, executable.as_deref().map(Path::new)).await
+}
+
+/// 应用启动或打开工作区时调用：该工作区上次会话崩溃遗留的后端仍占用端口时，确认是同一进程后结束它。
+/// PID 已被其他程序复用时不会结束任何进程
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn adopt_or_cleanup_orphan(
+    app: AppHandle,
+    workspace_id: Option<String>,
+) -> Result<OrphanReport, PortError> {
+    let workspace = backend::workspace_id(workspace_id)?;
+    run_blocking(move || cleanup_orphan(&app, &workspace)).await
+}
+
+/// 开启或关闭后端意外退出后的自动重启（指数退避，次数上限见 `BackendConfig::restart_policy`）
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn set_auto_restart(
+    app: AppHandle,
+    enabled: bool,
+    workspace_id: Option<String>,
+) -> Result<(), PortError> {
+    let workspace = backend::workspace_id(workspace_id)?;
+    set_auto_restart_enabled(&app, &workspace, enabled);
+    Ok(())
+}
+
+/// 工作区后端的运行状态与自动重启记录
+#[tauri::command]
+fn get_backend_status(
+    app: AppHandle,
+    workspace_id: Option<String>,
+) -> Result<BackendStatus, PortError> {
+    Ok(backend_status(&app, &backend::workspace_id(workspace_id)?))
+}
+
+/// 工作区后端最近 `lines` 行输出（所有工作区合计最多保留 2000 行），供日志面板打开时回填
+#[tauri::command]
+fn get_backend_log_tail(
+    log: State<'_, BackendLog>,
+    lines: usize,
+    workspace_id: Option<String>,
+) -> Result<Vec<LogLine>, PortError> {
+    Ok(log.tail(&backend::workspace_id(workspace_id)?, lines))
+}
+
+/// 工作区后端最近 `minutes` 分钟内的资源采样（CPU、内存、打开的文件描述符），用于绘制趋势图
+#[tauri::command]
+fn get_backend_metrics_history(
+    metrics: State<'_, BackendMetrics>,
+    minutes: u32,
+    workspace_id: Option<String>,
+) -> Result<Vec<MetricsSample>, PortError> {
+    Ok(metrics.history(&backend::workspace_id(workspace_id)?, minutes))
+}
+
+/// 磁盘上的历史后端日志（每个工作区单个文件 5 MiB，共保留 5 个），按时间先后排列
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn get_backend_log_files(app: AppHandle) -> Result<Vec<LogFileInfo>, PortError> {
+    run_blocking(move || list_log_files(&app)).await
+}
 
-        Ok(())
+/// 分页读取历史后端日志：从 `offset` 字节处读取最多 `limit` 字节的完整行
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn read_backend_log(
+    app: AppHandle,
+    file: String,
+    offset: u64,
+    limit: usize,
+) -> Result<LogChunk, PortError> {
+    run_blocking(move || read_log_file(&app, &file, offset, limit)).await
+}
+
+/// 请求后端 `/health` 并返回状态、版本与延迟；`url` 缺省为 Rust 侧为该工作区启动的后端
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn check_backend_health(
+    app: AppHandle,
+    url: Option<String>,
+    timeout_ms: u64,
+    workspace_id: Option<String>,
+) -> Result<HealthReport, PortError> {
+    let workspace = backend::workspace_id(workspace_id)?;
+    check_health(&app, &workspace, url, Duration::from_millis(timeout_ms)).await
+}
+
+/// 端口已监听不代表后端已就绪；请求 `http://{host}:{port}{path}`（`host` 缺省为 `127.0.0.1`），
+/// 返回 2xx 时为 `true`，连接被拒绝或超时返回 `false` 而不是错误；`host` 无法解析时返回 `InvalidHost`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn backend_healthy(
+    app: AppHandle,
+    port: u16,
+    path: String,
+    timeout_ms: u64,
+    host: Option<String>,
+) -> Result<bool, PortError> {
+    validate_port(port)?;
+    if let Some(host) = host.clone().filter(|host| !host.trim().is_empty()) {
+        run_blocking(move || resolve_host(&host)).await?;
+    }
+    let timeout = Duration::from_millis(timeout_ms);
+    probe_ready(&app, host.as_deref(), port, &path, timeout).await
+}
+
+/// 用默认浏览器打开本机后端 `http://127.0.0.1:{port}{path}`；`path` 须为空或以 `/` 开头，
+/// 含空格、控制字符或非 ASCII 字符时返回 `InvalidUrlPath`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn open_backend_in_browser(app: AppHandle, port: u16, path: String) -> Result<(), PortError> {
+    validate_port(port)?;
+    let url = backend_url(None, port, &path)?;
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| PortError::OpenUrlFailed {
+            reason: e.to_string(),
+            url,
+        })
+}
+
+/// 规范化为绝对路径并确认存在；Windows 上去掉 `canonicalize` 加上的 `\\?\` 前缀，资源管理器不识别这种写法
+fn existing_path(path: &str) -> Result<PathBuf, PortError> {
+    let canonical = std::fs::canonicalize(path).map_err(|_| PortError::PathNotFound {
+        path: path.to_string(),
+    })?;
+    #[cfg(windows)]
+    if let Some(rest) = canonical.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+        return Ok(match rest.strip_prefix(r"UNC\") {
+            Some(share) => PathBuf::from(format!(r"\\{share}")),
+            None => PathBuf::from(rest),
+        });
+    }
+    Ok(canonical)
+}
+
+/// 在系统文件管理器中显示并选中 `path`：Windows 资源管理器、macOS 访达，Linux 上通过
+/// `org.freedesktop.FileManager1` 选中，文件管理器不支持时打开所在目录。路径直接交给系统接口，
+/// 不经过命令行拼接，含空格、逗号与非 ASCII 字符的路径不需要转义。
+/// 路径不存在时返回 `PathNotFound`，没有可用的文件管理器时返回 `FileManagerUnavailable`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn reveal_in_file_manager(app: AppHandle, path: String) -> Result<(), PortError> {
+    run_blocking(move || {
+        let path = existing_path(&path)?;
+        app.opener()
+            .reveal_item_in_dir(&path)
+            .map_err(|e| PortError::FileManagerUnavailable {
+                reason: e.to_string(),
+            })
+    })
+    .await
+}
+
+/// 把本机后端地址 `http://127.0.0.1:{port}` 写入剪贴板，格式与 `open_backend_in_browser` 一致
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn copy_backend_url(app: AppHandle, port: u16) -> Result<(), PortError> {
+    validate_port(port)?;
+    let url = backend_url(None, port, "")?;
+    app.clipboard()
+        .write_text(url)
+        .map_err(|e| PortError::ClipboardFailed {
+            reason: e.to_string(),
+        })
+}
+
+/// 写入纯文本、HTML（同时写入纯文本版本）或 base64 编码的 PNG；超过 20 MB 时返回 `ClipboardTooLarge`
+#[tauri::command]
+#[tracing::instrument(skip(app, content))]
+async fn clipboard_write(app: AppHandle, content: ClipboardContent) -> Result<(), PortError> {
+    run_blocking(move || clipboard::write(&app, content)).await
+}
+
+/// 读取 `kinds` 中请求的数据并报告剪贴板中现有的类别；未请求图片时不读取图片
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn clipboard_read(
+    app: AppHandle,
+    kinds: Vec<ClipboardKind>,
+) -> Result<ClipboardData, PortError> {
+    run_blocking(move || clipboard::read(&app, &kinds)).await
+}
+
+/// 登录系统时是否自动启动本应用。每次都从系统读取（Windows 注册表 `Run` 键、macOS LaunchAgent、
+/// Linux `~/.config/autostart`），在系统设置中的修改也能反映出来；沙盒等环境下返回 `AutostartUnavailable`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn get_autostart(app: AppHandle) -> Result<bool, PortError> {
+    run_blocking(move || autostart::is_enabled(&app)).await
+}
+
+/// 开启或关闭登录时自动启动；重复调用结果相同，关闭时删除整个自启动项。
+/// `hidden` 为 `true` 时自启动后只显示托盘图标
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+async fn set_autostart(
+    app: AppHandle,
+    enabled: bool,
+    hidden: Option<bool>,
+) -> Result<(), PortError> {
+    run_blocking(move || autostart::set_enabled(&app, enabled, hidden.unwrap_or(false))).await
+}
+
+/// 取走冷启动时随命令行传入的 `openreview://` 链接，前端加载完成后调用一次；
+/// 之后收到的链接通过 `deeplink://action` / `deeplink://error` 事件发送
+#[tauri::command]
+fn take_pending_deep_links(links: State<'_, DeepLinks>) -> PendingDeepLinks {
+    links.take()
+}
+
+/// 清除 `label` 窗口保存的位置与大小，窗口移到屏幕外或大小异常时使用
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn reset_window_state(app: AppHandle, label: String) -> Result<(), PortError> {
+    window_state::reset(&app, &label)
+}
+
+/// 打开 `kind` 类别（`review`、`diff`）的次级窗口，返回用于 `close_window` 等的 label；
+/// `context` 在页面脚本执行前注入为 `window.__OPEN_REVIEW_WINDOW__.context`。未知类别返回 `UnknownWindowKind`
+// Windows 上在同步命令中创建窗口会死锁，因此为 async
+#[tauri::command]
+#[tracing::instrument(skip(app, context))]
+async fn open_secondary_window(
+    app: AppHandle,
+    kind: String,
+    context: Option<Value>,
+) -> Result<String, PortError> {
+    secondary_window::open(&app, &kind, &context.unwrap_or(Value::Null))
+}
+
+/// 关闭 `label` 窗口，窗口不存在时返回 `WindowNotFound`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn close_window(app: AppHandle, label: String) -> Result<(), PortError> {
+    secondary_window::close(&app, &label)
+}
+
+/// 所有已打开的窗口，主窗口在前
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn list_windows(app: AppHandle) -> Vec<WindowInfo> {
+    secondary_window::list(&app)
+}
+
+/// 发出系统通知；`notificationsEnabled` 关闭或该类别被静音时不发出。返回 `true` 表示已发出
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn notify(
+    app: AppHandle,
+    title: String,
+    body: String,
+    options: Option<NotifyOptions>,
+) -> Result<bool, PortError> {
+    notifications::notify(&app, &title, &body, options.unwrap_or_default())
+}
+
+/// 注册全局快捷键并保存到设置，`accelerator` 形如 `CommandOrControl+Shift+O`。内置 ID
+/// `toggle-main-window` 与 `toggle-backend` 由 Rust 侧直接处理；所有快捷键按下时都发送 `shortcut://triggered`。
+/// 格式错误返回 `InvalidShortcut`，与其他 ID 重复或被系统与其他程序占用时返回 `ShortcutConflict`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn register_shortcut(app: AppHandle, id: String, accelerator: String) -> Result<(), PortError> {
+    shortcuts::register(&app, id, accelerator)
+}
+
+/// 注销快捷键并从设置中删除
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn unregister_shortcut(app: AppHandle, id: String) -> Result<(), PortError> {
+    shortcuts::unregister(&app, &id)
+}
+
+/// 设置中的全部快捷键及其是否已注册
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn list_shortcuts(app: AppHandle) -> Vec<ShortcutBinding> {
+    shortcuts::list(&app)
+}
+
+/// 当前进程是否以管理员身份运行；非 Windows 平台始终为 `true`。
+/// 结束其他用户或服务占用端口的进程前可据此提示提权
+#[tauri::command]
+fn is_elevated() -> bool {
+    elevation::is_elevated()
+}
+
+/// 以管理员身份重新启动应用（弹出 UAC 确认），成功后退出当前实例；
+/// 用户取消时返回 `ElevationCancelled`，非 Windows 平台返回 `ElevationUnsupported`
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+fn relaunch_elevated(app: AppHandle) -> Result<(), PortError> {
+    elevation::relaunch_elevated(&app)
+}
+
+/// 再次启动应用时发送给已运行实例的事件，载荷为 [`SecondInstance`]
+#[cfg(desktop)]
+const SECOND_INSTANCE_EVENT: &str = "app://second-instance";
+
+/// `app://second-instance` 事件载荷：新实例的命令行参数与工作目录
+#[cfg(desktop)]
+#[derive(Debug, Clone, serde::Serialize)]
+struct SecondInstance {
+    args: Vec<String>,
+    cwd: String,
+}
+
+/// 再次启动时不创建新实例（否则两个实例会争抢同一个后端端口），
+/// 而是聚焦已有窗口并转发新实例的参数
+#[cfg(desktop)]
+fn on_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+    use tauri::Emitter;
+
+    tracing::info!(?args, "检测到重复启动，聚焦已有窗口");
+    tray::show_main_window(app);
+    let (event, payload) = second_instance_event(args, cwd);
+    let _ = app.emit(event, payload);
+}
+
+/// 新实例的参数原样转发（`args[0]` 为可执行文件路径），由前端决定如何处理
+#[cfg(desktop)]
+fn second_instance_event(args: Vec<String>, cwd: String) -> (&'static str, SecondInstance) {
+    (SECOND_INSTANCE_EVENT, SecondInstance { args, cwd })
+}
+
+/// 记录窗口位置，处理通知的点击与拖入的文件；关闭主窗口时依次尝试隐藏到托盘、按设置先停止后端，都不适用时正常关闭
+#[cfg(desktop)]
+fn on_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+    use tauri::Manager;
+
+    window_state::on_window_event(window, event);
+    notifications::on_window_event(window, event);
+    attachments::on_window_event(window, event);
+    let tauri::WindowEvent::CloseRequested { api, .. } = event else {
+        return;
+    };
+    if window.label() != MAIN_WINDOW || tray::hide_on_close(window, api) {
+        return;
+    }
+    if settings::load(window.app_handle()).stop_backend_on_close && stop_before_close(window) {
+        api.prevent_close();
     }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    #[cfg(windows)]
+    elevation::wait_for_previous_instance();
+    let builder = tauri::Builder::default();
+    // 必须最先注册，保证重复启动时在其他插件初始化前退出
+    #[cfg(desktop)]
+    let builder = builder
+        .plugin(tauri_plugin_single_instance::init(on_second_instance))
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(shortcuts::on_shortcut)
+                .build(),
+        )
+        .manage(shortcuts::Shortcuts::default())
+        .on_window_event(on_window_event);
+    builder
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_store::Builder::default().build())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            // 先迁移再读取日志级别；迁移的结果在日志初始化后才能记录
+            let migrated = settings::migrate(app.handle());
+            logging::init(app.handle(), settings::load(app.handle()).log_level);
+            if let Err(error) = migrated {
+                tracing::warn!(%error, "设置迁移失败，沿用旧格式读取");
+            }
+            if let Err(error) = secrets::migrate_store_secrets(app.handle()) {
+                tracing::warn!(%error, "无法将明文密钥移入钥匙串，下次启动时重试");
+            }
+            window_state::restore_all(app.handle());
+            #[cfg(desktop)]
+            tray::init(app.handle());
+            #[cfg(desktop)]
+            shortcuts::init(app.handle());
+            deeplink::init(app.handle());
+            notifications::init(app.handle());
+            Ok(())
+        })
+        .manage(BackendState::default())
+        .manage(PortReservations::default())
+        .manage(HealthClient::default())
+        .manage(BackendLog::default())
+        .manage(BackendMetrics::default())
+        .manage(PortWaits::default())
+        .manage(Secrets::default())
+        .manage(DeepLinks::default())
+        .manage(WindowStates::default())
+        .manage(Notifications::default())
         .invoke_handler(tauri::generate_handler![
-            greet,
+            app_version,
+            port_in_use,
             is_port_in_use,
-            force_kill_process_on_port
+            udp_port_in_use,
+            check_port,
+            probe_port,
+            can_connect,
+            ports_in_use,
+            wait_for_port,
+            cancel_wait,
+            find_free_port,
+            get_saved_port,
+            set_saved_port,
+            get_processes_on_port,
+            list_processes_on_port,
+            process_stats_on_port,
+            scan_ports,
+            listening_ports,
+            kill_process_tree,
+            kill_pid,
+            force_kill_process_on_port,
+            force_kill_ports,
+            reserve_port,
+            release_port,
+            start_backend,
+            start_backend_and_wait,
+            stop_backend,
+            restart_backend,
+            adopt_or_cleanup_orphan,
+            validate_backend_path,
+            get_backend_version,
+            resolve_port_conflict,
+            set_auto_restart,
+            get_backend_status,
+            set_backend_env,
+            get_settings,
+            update_settings,
+            validate_settings,
+            reset_settings,
+            restore_settings_backup,
+            export_settings,
+            import_settings,
+            set_secret,
+            get_secret,
+            delete_secret,
+            get_secret_backend,
+            get_backend_log_tail,
+            get_backend_metrics_history,
+            get_backend_log_files,
+            read_backend_log,
+            check_backend_health,
+            backend_healthy,
+            open_backend_in_browser,
+            copy_backend_url,
+            reveal_in_file_manager,
+            clipboard_write,
+            clipboard_read,
+            get_autostart,
+            set_autostart,
+            take_pending_deep_links,
+            reset_window_state,
+            open_secondary_window,
+            close_window,
+            list_windows,
+            notify,
+            register_shortcut,
+            unregister_shortcut,
+            list_shortcuts,
+            is_elevated,
+            relaunch_elevated
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        // 关闭最后一个窗口与 Cmd+Q 都会产生 `ExitRequested`
+        .run(|app, event| match event {
+            RunEvent::ExitRequested { code, api, .. }
+                if !settings::load(app).keep_backend_on_exit && stop_before_exit(app, code) =>
+            {
+                api.prevent_exit();
+            }
+            RunEvent::Exit if !settings::load(app).keep_backend_on_exit => kill_on_exit(app),
+            _ => {}
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app_version_is_the_crate_version() {
+        let version = app_version();
+        let (semver, hash) = match version.split_once('+') {
+            Some((semver, hash)) => (semver, Some(hash)),
+            None => (version.as_str(), None),
+        };
+        assert_eq!(semver, env!("CARGO_PKG_VERSION"));
+        assert_eq!(hash, option_env!("GIT_COMMIT_HASH"));
+    }
+
+    #[test]
+    fn batch_kill_reports_every_failed_port() {
+        let first = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let second = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let first_port = first.local_addr().unwrap().port();
+        let second_port = second.local_addr().unwrap().port();
+        let free_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let options = KillOptions {
+            grace: Duration::from_millis(100),
+            allow_self: false,
+            backends: Vec::new(),
+            include_children: false,
+            expected_names: None,
+            protocol: Protocol::Tcp,
+            dry_run: false,
+        };
+
+        // 两个端口都由测试进程自身监听，均应以 WouldKillSelf 失败，空闲端口照常成功
+        let ports = vec![second_port, first_port, free_port, first_port];
+        match kill_ports(ports, &options) {
+            Err(PortError::PartialKillFailure(failure)) => {
+                assert_eq!(failure.killed, HashMap::from([(free_port, Vec::new())]));
+                assert_eq!(failure.errors.len(), 2);
+                for port in [first_port, second_port] {
+                    assert!(matches!(
+                        failure.errors.get(&port),
+                        Some(PortError::WouldKillSelf)
+                    ));
+                }
+            }
+            other => panic!("应返回 PartialKillFailure: {other:?}"),
+        }
+        drop((first, second));
+    }
+
+    #[test]
+    fn batch_check_matches_each_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let bound = listener.local_addr().unwrap().port();
+        let free = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let in_use = tauri::async_runtime::block_on(ports_in_use(vec![bound, free])).unwrap();
+        assert_eq!(in_use, vec![true, false]);
+        assert!(matches!(
+            tauri::async_runtime::block_on(ports_in_use(vec![bound, 0])),
+            Err(PortError::InvalidPort { .. })
+        ));
+        drop(listener);
+    }
+
+    #[test]
+    fn processes_on_port_include_our_own_pid() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert_eq!(
+            pids_listening_on(port, Protocol::Tcp).unwrap(),
+            vec![std::process::id()]
+        );
+        let processes = processes_on_port(port).unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].pid, std::process::id());
+        drop(listener);
+        assert!(processes_on_port(port).unwrap().is_empty());
+    }
+
+    #[test]
+    fn diagnostics_list_the_test_binary_by_its_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let exe = std::env::current_exe().unwrap();
+        let exe_name = exe.file_name().unwrap().to_string_lossy();
+
+        let processes = tauri::async_runtime::block_on(list_processes_on_port(port)).unwrap();
+        assert_eq!(processes.len(), 1);
+        let process = &processes[0];
+        assert_eq!(process.pid, std::process::id());
+        // 进程名可能被系统截断（Linux 为 15 个字符），只比较前缀
+        assert!(!process.name.is_empty());
+        assert!(exe_name.starts_with(&process.name), "{process:?}");
+        assert!(
+            process.command_line.contains(exe_name.as_ref()),
+            "{process:?}"
+        );
+        drop(listener);
+    }
+
+    #[test]
+    fn backend_urls_are_assembled_for_each_kind_of_host() {
+        let cases = [
+            (Some("192.168.1.20"), "http://192.168.1.20:8080/health"),
+            (Some("::1"), "http://[::1]:8080/health"),
+            (Some("[fe80::1]"), "http://[fe80::1]:8080/health"),
+            (Some("devbox.local"), "http://devbox.local:8080/health"),
+            (None, "http://127.0.0.1:8080/health"),
+        ];
+        for (host, expected) in cases {
+            assert_eq!(backend_url(host, 8080, "/health").unwrap(), expected);
+        }
+        for path in ["health", "/a b", "/tab\t", "/报告"] {
+            assert!(
+                matches!(
+                    backend_url(None, 8080, path),
+                    Err(PortError::InvalidUrlPath { .. })
+                ),
+                "{path:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn async_port_checks_report_a_bound_port() {
+        use tauri::async_runtime::block_on;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(block_on(port_in_use(port, None, None)).unwrap());
+        assert!(block_on(is_port_in_use(port, None, None, None, None)).unwrap());
+        drop(listener);
+        assert!(!block_on(port_in_use(port, None, None)).unwrap());
+
+        // 阻塞任务中的错误原样返回
+        assert!(matches!(
+            block_on(run_blocking(|| validate_port(0))),
+            Err(PortError::InvalidPort { port: 0 })
+        ));
+        assert_eq!(block_on(run_blocking(move || Ok(port))).unwrap(), port);
+    }
+
+    #[cfg(desktop)]
+    #[test]
+    fn second_launch_forwards_its_argv_verbatim() {
+        let args = vec![
+            "/opt/Open Reviewer/open-reviewer".to_string(),
+            "--workspace".to_string(),
+            "评审 1".to_string(),
+            "openreview://open?file=a.diff".to_string(),
+        ];
+        let (event, payload) = second_instance_event(args.clone(), "/home/me".to_string());
+        assert_eq!(event, "app://second-instance");
+        assert_eq!(
+            serde_json::to_value(payload).unwrap(),
+            serde_json::json!({ "args": args, "cwd": "/home/me" })
+        );
+    }
+
+    /// `invoke_handler` 中注册的命令名，直接从本文件的源码中读取
+    fn registered_commands() -> Vec<&'static str> {
+        let source = include_str!("lib.rs");
+        let start = source.find("generate_handler![").unwrap() + "generate_handler![".len();
+        let end = start + source[start..].find(']').unwrap();
+        source[start..end]
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn template_greet_command_is_not_registered() {
+        let commands = registered_commands();
+        assert!(commands.contains(&"app_version"), "{commands:?}");
+        assert!(!commands.contains(&"greet"), "{commands:?}");
+    }
 }
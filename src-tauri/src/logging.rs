@@ -0,0 +1,129 @@
+use tauri::{AppHandle, Manager};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::settings::LogLevel;
+
+const LOG_FILE_PREFIX: &str = "open-reviewer";
+/// 按天滚动，保留最近 7 天的日志
+const MAX_LOG_FILES: usize = 7;
+
+/// 运行期间替换日志过滤规则；设置了 `RUST_LOG` 时不注册，以环境变量为准
+struct LogFilter(reload::Handle<EnvFilter, Registry>);
+
+/// 初始化日志：同时输出到 stderr 与应用日志目录下按天滚动的文件；
+/// 日志目录不可用时只输出到 stderr。未设置 `RUST_LOG` 时按设置中的 `level` 过滤，之后可通过 `set_level` 调整
+pub(crate) fn init(app: &AppHandle, level: LogLevel) {
+    let from_env = EnvFilter::try_from_default_env().ok();
+    let reloadable = from_env.is_none();
+    let (filter, handle) =
+        reload::Layer::new(from_env.unwrap_or_else(|| EnvFilter::new(level.filter())));
+
+    let appender = app.path().app_log_dir().ok().map(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+    });
+    // 订阅器初始化前无处记录，失败原因在初始化后经 stderr 层输出
+    let (file_layer, file_error) = match appender {
+        Some(Ok(appender)) => (
+            Some(fmt::layer().with_ansi(false).with_writer(appender)),
+            None,
+        ),
+        Some(Err(error)) => (None, Some(error.to_string())),
+        None => (None, None),
+    };
+
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .try_init();
+    if let Some(error) = file_error {
+        tracing::warn!(%error, "无法创建日志文件，只输出到 stderr");
+    }
+    if reloadable {
+        app.manage(LogFilter(handle));
+    }
+}
+
+/// 设置中的日志级别变化时立即生效；设置了 `RUST_LOG` 时忽略
+pub(crate) fn set_level(app: &AppHandle, level: LogLevel) {
+    let Some(filter) = app.try_state::<LogFilter>() else {
+        return;
+    };
+    if let Err(error) = filter.0.reload(EnvFilter::new(level.filter())) {
+        tracing::warn!(%error, "无法更新日志级别");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write as _;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id};
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer};
+
+    use super::*;
+    use crate::ports::{pids_listening_on, Protocol};
+
+    /// 记录每个 span 与事件的名称和字段，格式为 `名称 字段=值 ...`
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for Capture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            let mut fields = Fields(format!("span {}", attrs.metadata().name()));
+            attrs.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let mut fields = Fields("event".to_string());
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    #[test]
+    fn commands_record_spans_and_resolved_pids() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            // 命令内的阻塞任务运行在其他线程上，不经过这里的线程局部订阅器，直接调用解析函数
+            pids_listening_on(port, Protocol::Tcp).unwrap();
+            tauri::async_runtime::block_on(crate::get_processes_on_port(port)).unwrap();
+        });
+        drop(listener);
+
+        let records = capture.0.lock().unwrap();
+        let span = format!("span get_processes_on_port port={port}");
+        assert!(records.contains(&span), "{records:?}");
+        let pids = format!("pids=[{}]", std::process::id());
+        assert!(
+            records.iter().any(|record| record.starts_with("event")
+                && record.contains("已解析端口占用进程")
+                && record.contains(&format!("port={port}"))
+                && record.contains(&pids)),
+            "{records:?}"
+        );
+    }
+}
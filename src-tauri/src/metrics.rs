@@ -0,0 +1,167 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::sleep;
+use tracing::debug;
+
+use crate::backend::{running_backend, BackendHandle};
+use crate::backend_log::now_millis;
+
+/// 每次采样后发送，载荷为 [`MetricsSample`]
+pub(crate) const BACKEND_METRICS_EVENT: &str = "backend://metrics";
+/// 默认采样间隔；只刷新单个进程，开销可以忽略
+pub(crate) const DEFAULT_METRICS_INTERVAL: Duration = Duration::from_secs(5);
+/// 采样间隔下限，避免采样本身占用明显的 CPU
+const MIN_METRICS_INTERVAL: Duration = Duration::from_secs(1);
+/// 每个工作区在内存中保留的样本数，默认间隔下约 1 小时
+const MAX_SAMPLES: usize = 720;
+
+/// 后端进程的一次资源采样；`timestamp` 为 Unix 毫秒时间戳，
+/// `open_handles` 为打开的文件描述符数，目前只在 Linux 上提供
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsSample {
+    pub workspace_id: String,
+    pub timestamp: u64,
+    pub pid: u32,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub open_handles: Option<u64>,
+}
+
+/// 各工作区后端的资源采样历史，通过 `.manage()` 注册为全局状态
+#[derive(Default)]
+pub(crate) struct BackendMetrics {
+    samples: Mutex<HashMap<String, VecDeque<MetricsSample>>>,
+}
+
+impl BackendMetrics {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, VecDeque<MetricsSample>>> {
+        self.samples.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn push(&self, sample: MetricsSample) {
+        let mut samples = self.lock();
+        let samples = samples.entry(sample.workspace_id.clone()).or_default();
+        if samples.len() == MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    /// `workspace` 最近 `minutes` 分钟内的样本，按时间先后排列
+    pub(crate) fn history(&self, workspace: &str, minutes: u32) -> Vec<MetricsSample> {
+        let since = now_millis().saturating_sub(u64::from(minutes) * 60_000);
+        self.lock()
+            .get(workspace)
+            .into_iter()
+            .flatten()
+            .filter(|sample| sample.timestamp >= since)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open_handles(pid: u32) -> Option<u64> {
+    let fds = std::fs::read_dir(format!("/proc/{pid}/fd")).ok()?;
+    Some(fds.count() as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_handles(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// 单个进程的采样器：复用同一个 System，CPU 占用按两次刷新之间的差值计算
+struct ProcessSampler {
+    system: System,
+    target: [Pid; 1],
+    refresh: ProcessRefreshKind,
+    start_time: u64,
+}
+
+impl ProcessSampler {
+    /// 进程不存在时返回 `None`
+    fn new(pid: u32) -> Option<Self> {
+        let target = [Pid::from_u32(pid)];
+        let refresh = ProcessRefreshKind::nothing().with_cpu().with_memory();
+        let mut system = System::new();
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&target), true, refresh);
+        let start_time = system.process(target[0])?.start_time();
+        Some(Self {
+            system,
+            target,
+            refresh,
+            start_time,
+        })
+    }
+
+    /// 进程已退出或 PID 被复用（启动时间不同）时返回 `None`
+    fn sample(&mut self, workspace_id: &str) -> Option<MetricsSample> {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&self.target),
+            true,
+            self.refresh,
+        );
+        let process = self
+            .system
+            .process(self.target[0])
+            .filter(|process| process.start_time() == self.start_time)?;
+        let pid = self.target[0].as_u32();
+        Some(MetricsSample {
+            workspace_id: workspace_id.to_string(),
+            timestamp: now_millis(),
+            pid,
+            cpu_percent: process.cpu_usage(),
+            memory_bytes: process.memory(),
+            open_handles: open_handles(pid),
+        })
+    }
+}
+
+/// 按 `interval` 采样后端进程直到它不再是所在工作区托管的后端。
+/// 每次采样都核对启动时间，PID 被复用时立即停止
+pub(crate) async fn sample_backend(app: AppHandle, backend: BackendHandle, interval: Duration) {
+    let BackendHandle {
+        workspace_id, pid, ..
+    } = backend;
+    let interval = interval.max(MIN_METRICS_INTERVAL);
+    let Some(mut sampler) = ProcessSampler::new(pid) else {
+        return;
+    };
+
+    loop {
+        sleep(interval).await;
+        if running_backend(&app, &workspace_id).is_none_or(|backend| backend.pid != pid) {
+            break;
+        }
+        let Some(sample) = sampler.sample(&workspace_id) else {
+            break;
+        };
+        app.state::<BackendMetrics>().push(sample.clone());
+        let _ = app.emit(BACKEND_METRICS_EVENT, sample);
+    }
+    debug!(%workspace_id, pid, "后端资源采样已停止");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_process_is_sampled_with_nonzero_memory() {
+        let pid = std::process::id();
+        let mut sampler = ProcessSampler::new(pid).unwrap();
+        let sample = sampler.sample("ws").unwrap();
+        assert_eq!(sample.workspace_id, "ws");
+        assert_eq!(sample.pid, pid);
+        assert!(sample.memory_bytes > 0);
+        #[cfg(target_os = "linux")]
+        assert!(sample.open_handles.is_some_and(|handles| handles > 0));
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Listener, Manager, Window, WindowEvent};
+use tauri_plugin_notification::NotificationExt;
+use tracing::warn;
+
+use crate::backend::{BACKEND_CRASHED_EVENT, BACKEND_GAVE_UP_EVENT};
+use crate::ports::PortError;
+use crate::{settings, MAIN_WINDOW};
+
+/// 在后台发出通知后主窗口首次获得焦点时发送给主窗口，载荷为 [`NotificationClicked`]
+pub(crate) const NOTIFICATION_CLICKED_EVENT: &str = "notification://clicked";
+
+/// 通知类别，可在设置 `mutedNotifications` 中逐类静音
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationCategory {
+    /// 后端意外退出或自动重启次数用尽，由 Rust 侧自动发出
+    BackendCrashed,
+    /// 评审同步完成，由前端调用 `notify` 发出
+    SyncComplete,
+    /// 有新版本可用，由前端调用 `notify` 发出
+    UpdateAvailable,
+}
+
+/// `notify` 的可选参数
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyOptions {
+    /// 相同 `id` 的通知替换之前的通知而不是叠加；未指定时每次都是新通知
+    pub id: Option<String>,
+    /// 未指定时只受 `notificationsEnabled` 控制
+    pub category: Option<NotificationCategory>,
+    /// 原样放入 `notification://clicked` 的载荷，供前端跳转
+    pub context: Option<Value>,
+    /// 为 `true` 时主窗口可见且聚焦则不发出
+    pub background_only: Option<bool>,
+}
+
+/// `notification://clicked` 事件载荷
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationClicked {
+    pub id: Option<String>,
+    pub category: Option<NotificationCategory>,
+    pub context: Option<Value>,
+}
+
+/// 最近一条在后台发出的通知，通过 `.manage()` 注册为全局状态
+#[derive(Default)]
+pub(crate) struct Notifications {
+    pending: Mutex<Option<NotificationClicked>>,
+}
+
+impl Notifications {
+    fn lock(&self) -> MutexGuard<'_, Option<NotificationClicked>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// 系统通知的数字 ID 由字符串 `id` 经 FNV-1a 得到，同一 `id` 每次运行都相同
+fn numeric_id(id: &str) -> i32 {
+    let hash = id.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    (hash & 0x7fff_ffff) as i32
+}
+
+/// 主窗口不存在、隐藏到托盘、最小化或未聚焦时视为在后台
+fn in_background(app: &AppHandle) -> bool {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return true;
+    };
+    !window.is_visible().unwrap_or(false)
+        || window.is_minimized().unwrap_or(false)
+        || !window.is_focused().unwrap_or(false)
+}
+
+/// 按设置与窗口状态决定是否发出通知；返回 `true` 表示已发出
+pub(crate) fn notify(
+    app: &AppHandle,
+    title: &str,
+    body: &str,
+    options: NotifyOptions,
+) -> Result<bool, PortError> {
+    let settings = settings::load(app);
+    let muted = options
+        .category
+        .is_some_and(|category| settings.muted_notifications.get(&category) == Some(&true));
+    if !settings.notifications_enabled || muted {
+        return Ok(false);
+    }
+    let background = in_background(app);
+    if options.background_only.unwrap_or(false) && !background {
+        return Ok(false);
+    }
+
+    let mut builder = app.notification().builder().title(title).body(body);
+    if let Some(id) = &options.id {
+        builder = builder.id(numeric_id(id));
+    }
+    builder.show().map_err(|e| PortError::NotificationFailed {
+        reason: e.to_string(),
+    })?;
+    if background {
+        *app.state::<Notifications>().lock() = Some(NotificationClicked {
+            id: options.id,
+            category: options.category,
+            context: options.context,
+        });
+    }
+    Ok(true)
+}
+
+/// 后端事件自动发出的通知：只在主窗口处于后台时发出，同一工作区的通知互相替换
+fn notify_backend(app: &AppHandle, payload: &str, describe: fn(&str, &Value) -> String) {
+    let Ok(context) = serde_json::from_str::<Value>(payload) else {
+        return;
+    };
+    let workspace = context
+        .get("workspaceId")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let options = NotifyOptions {
+        id: Some(format!("backend-crashed:{workspace}")),
+        category: Some(NotificationCategory::BackendCrashed),
+        background_only: Some(true),
+        context: Some(context.clone()),
+    };
+    let body = describe(&workspace, &context);
+    if let Err(error) = notify(app, &app.package_info().name, &body, options) {
+        warn!(%error, "无法发出后端通知");
+    }
+}
+
+/// 订阅后端崩溃与放弃重启事件
+pub(crate) fn init(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen(BACKEND_CRASHED_EVENT, move |event| {
+        notify_backend(&handle, event.payload(), |workspace, _| {
+            format!("后端（{workspace}）意外退出")
+        });
+    });
+    let handle = app.clone();
+    app.listen(BACKEND_GAVE_UP_EVENT, move |event| {
+        notify_backend(&handle, event.payload(), |workspace, context| {
+            let attempts = context
+                .get("attempts")
+                .and_then(Value::as_u64)
+                .unwrap_or_default();
+            format!("后端（{workspace}）已重启 {attempts} 次仍未恢复，不再自动重启")
+        });
+    });
+}
+
+/// 桌面端通知插件不提供点击回调；点击通知会激活应用，因此把后台发出通知后主窗口首次获得焦点
+/// 视为点击，取出最近一条通知发送 `notification://clicked`
+#[cfg_attr(mobile, allow(dead_code))]
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    if window.label() != MAIN_WINDOW || !matches!(event, WindowEvent::Focused(true)) {
+        return;
+    }
+    let app = window.app_handle();
+    let Some(clicked) = app.state::<Notifications>().lock().take() else {
+        return;
+    };
+    let _ = app.emit_to(MAIN_WINDOW, NOTIFICATION_CLICKED_EVENT, clicked);
+}
@@ -0,0 +1,259 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::backend::{running_backend, DEFAULT_WORKSPACE};
+use crate::ports::{can_bind, PortError, WAIT_POLL_INTERVAL};
+use crate::process::{kill_tree, process_alive, process_identity};
+
+/// 应用数据目录下记录默认工作区后端进程的文件，其他工作区为 `backend-<ID>.pid.json`
+const PID_FILE_NAME: &str = "backend.pid.json";
+/// 结束遗留后端时给予的正常退出时间
+const ORPHAN_KILL_GRACE: Duration = Duration::from_secs(3);
+/// 结束遗留后端后等待端口释放的最长时间
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(2);
+/// sysinfo 在不同时刻换算出的启动时间可能相差 1 秒
+const START_TIME_TOLERANCE_SECS: u64 = 1;
+
+/// 启动后端时写入 PID 文件的内容；`start_time` 为 Unix 时间戳（秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PidRecord {
+    pub pid: u32,
+    pub port: u16,
+    pub start_time: u64,
+    pub executable: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrphanOutcome {
+    /// 没有 PID 文件，上次正常退出
+    NoPidFile,
+    /// 记录的进程已经退出
+    NotRunning,
+    /// 记录的就是当前正在管理的后端
+    Managed,
+    /// PID 已被其他程序复用或无法确认身份，没有结束任何进程
+    Mismatch,
+    /// 已结束上次遗留的后端
+    Killed,
+}
+
+/// `adopt_or_cleanup_orphan` 的结果；`port_freed` 表示处理后记录的端口是否可用
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanReport {
+    pub outcome: OrphanOutcome,
+    pub record: Option<PidRecord>,
+    pub port_freed: bool,
+}
+
+fn pid_file(app: &AppHandle, workspace: &str) -> Option<PathBuf> {
+    let name = if workspace == DEFAULT_WORKSPACE {
+        PID_FILE_NAME.to_string()
+    } else {
+        format!("backend-{workspace}.pid.json")
+    };
+    app.path().app_data_dir().ok().map(|dir| dir.join(name))
+}
+
+fn read_record(app: &AppHandle, workspace: &str) -> Option<PidRecord> {
+    let content = fs::read_to_string(pid_file(app, workspace)?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// PID 文件中记录的 `workspace` 上次启动的后端 PID
+pub(crate) fn recorded_pid(app: &AppHandle, workspace: &str) -> Option<u32> {
+    read_record(app, workspace).map(|record| record.pid)
+}
+
+/// 后端启动后记录其 PID、端口与进程标识，应用崩溃后下次启动据此清理遗留进程
+pub(crate) fn write_pid_file(app: &AppHandle, workspace: &str, pid: u32, port: u16) {
+    let Some(path) = pid_file(app, workspace) else {
+        return;
+    };
+    let (start_time, executable) = process_identity(pid).unwrap_or_default();
+    let record = PidRecord {
+        pid,
+        port,
+        start_time,
+        executable,
+    };
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| {
+            let content = serde_json::to_string(&record).map_err(std::io::Error::other)?;
+            fs::write(&path, content)
+        });
+    if let Err(error) = written {
+        warn!(pid, %error, "写入后端 PID 文件失败");
+    }
+}
+
+/// 后端正常退出后删除 PID 文件；文件已记录了更新的后端时保留
+pub(crate) fn remove_pid_file(app: &AppHandle, workspace: &str, pid: u32) {
+    let Some(path) = pid_file(app, workspace) else {
+        return;
+    };
+    if read_record(app, workspace).is_some_and(|record| record.pid != pid) {
+        return;
+    }
+    let _ = fs::remove_file(path);
+}
+
+/// 启动时间与可执行文件都与记录一致才认为是同一个进程；无法读取可执行文件时一律视为不一致
+fn matches_record(record: &PidRecord) -> bool {
+    let Some((start_time, executable)) = process_identity(record.pid) else {
+        return false;
+    };
+    record.executable.is_some()
+        && executable == record.executable
+        && start_time.abs_diff(record.start_time) <= START_TIME_TOLERANCE_SECS
+}
+
+/// 检查 `workspace` 上次会话遗留的 PID 文件：记录的进程仍在运行且确认是上次启动的后端时结束它并释放端口。
+/// PID 被其他程序复用时绝不结束进程。处理完成后删除 PID 文件
+pub(crate) fn cleanup_orphan(app: &AppHandle, workspace: &str) -> Result<OrphanReport, PortError> {
+    let Some(record) = read_record(app, workspace) else {
+        return Ok(OrphanReport {
+            outcome: OrphanOutcome::NoPidFile,
+            record: None,
+            port_freed: true,
+        });
+    };
+    let report = |outcome| OrphanReport {
+        outcome,
+        port_freed: can_bind(record.port),
+        record: Some(record.clone()),
+    };
+
+    if running_backend(app, workspace).is_some_and(|backend| backend.pid == record.pid) {
+        return Ok(report(OrphanOutcome::Managed));
+    }
+    if let Some(path) = pid_file(app, workspace) {
+        let _ = fs::remove_file(path);
+    }
+    reap(workspace, &record).map(report)
+}
+
+/// 处理遗留记录：进程已退出或身份不一致时不结束任何进程，确认是上次启动的后端时结束它并等待端口释放
+fn reap(workspace: &str, record: &PidRecord) -> Result<OrphanOutcome, PortError> {
+    if !process_alive(record.pid) {
+        return Ok(OrphanOutcome::NotRunning);
+    }
+    if !matches_record(record) {
+        warn!(
+            pid = record.pid,
+            "PID 文件中的进程身份不一致，可能已被复用，跳过"
+        );
+        return Ok(OrphanOutcome::Mismatch);
+    }
+
+    info!(
+        workspace,
+        pid = record.pid,
+        port = record.port,
+        "结束上次遗留的后端"
+    );
+    match kill_tree(record.pid, false, ORPHAN_KILL_GRACE) {
+        Ok(_) | Err(PortError::NoSuchProcess { .. }) => {}
+        Err(error) => return Err(error),
+    }
+    let deadline = Instant::now() + PORT_RELEASE_TIMEOUT;
+    while !can_bind(record.port) && Instant::now() < deadline {
+        thread::sleep(WAIT_POLL_INTERVAL);
+    }
+    Ok(OrphanOutcome::Killed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::process::{Child, Command, Stdio};
+
+    use super::*;
+
+    fn free_port() -> u16 {
+        TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    /// 按 `write_pid_file` 的方式为 `child` 生成记录
+    fn record_for(child: &Child) -> PidRecord {
+        let (start_time, executable) = process_identity(child.id()).unwrap();
+        PidRecord {
+            pid: child.id(),
+            port: free_port(),
+            start_time,
+            executable,
+        }
+    }
+
+    #[test]
+    fn stale_record_of_an_exited_process_is_not_running() {
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        let record = record_for(&child);
+        child.wait().unwrap();
+        assert!(matches!(
+            reap(DEFAULT_WORKSPACE, &record),
+            Ok(OrphanOutcome::NotRunning)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn live_process_with_another_identity_is_left_alone() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        // PID 被复用：同一个 PID，但启动时间与上次的后端不同
+        let record = PidRecord {
+            start_time: 1,
+            ..record_for(&child)
+        };
+        assert!(matches!(
+            reap(DEFAULT_WORKSPACE, &record),
+            Ok(OrphanOutcome::Mismatch)
+        ));
+        let unknown = PidRecord {
+            executable: None,
+            ..record_for(&child)
+        };
+        assert!(matches!(
+            reap(DEFAULT_WORKSPACE, &unknown),
+            Ok(OrphanOutcome::Mismatch)
+        ));
+        assert!(child.try_wait().unwrap().is_none());
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn leftover_backend_is_killed() {
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+        let record = record_for(&child);
+        // 由单独的线程回收子进程，否则僵尸进程会一直被视为存活
+        let reaper = thread::spawn(move || {
+            let mut child = child;
+            child.wait()
+        });
+        assert!(matches!(
+            reap(DEFAULT_WORKSPACE, &record),
+            Ok(OrphanOutcome::Killed)
+        ));
+        assert!(!reaper.join().unwrap().unwrap().success());
+    }
+}
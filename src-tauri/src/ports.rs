@@ -0,0 +1,1743 @@
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::io;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, ToSocketAddrs, UdpSocket};
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(target_os = "windows")]
+use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, TcpState};
+use serde::{Deserialize, Serialize};
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::{timeout, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use crate::binary::BackendVersionInfo;
+
+/// 低于该值的端口需要特权，默认不参与空闲端口扫描
+pub(crate) const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+/// 未指定 host 时依次探测的地址，覆盖 IPv4 / IPv6 的回环与通配地址
+const DEFAULT_PROBE_HOSTS: [IpAddr; 4] = [
+    IpAddr::V4(Ipv4Addr::LOCALHOST),
+    IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+    IpAddr::V6(Ipv6Addr::LOCALHOST),
+    IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+];
+/// 回环地址；连接探测也只使用这两个地址，服务可能只监听其中一个地址族
+const LOOPBACK_HOSTS: [IpAddr; 2] = [
+    IpAddr::V4(Ipv4Addr::LOCALHOST),
+    IpAddr::V6(Ipv6Addr::LOCALHOST),
+];
+const WILDCARD_HOSTS: [IpAddr; 2] = [
+    IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+    IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+];
+const CONNECT_ATTEMPT_TIMEOUT: Duration = Duration::from_millis(200);
+/// `can_connect_to` 解析主机名的最长时间，不占用调用方给出的连接超时
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(2);
+pub(crate) const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// 外部命令（netstat / lsof / taskkill 等）的最长执行时间，超时后结束该命令
+const TOOL_TIMEOUT: Duration = Duration::from_secs(10);
+const TOOL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// 负载较高时 netstat 偶尔以退出码 1 结束且没有任何输出，重试几次通常就能成功
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const NETSTAT_ATTEMPTS: u32 = 3;
+/// 重试间隔，每次重试按尝试次数倍增
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const TOOL_RETRY_BACKOFF: Duration = Duration::from_millis(150);
+/// 批量探测端口时的并发线程数
+const PROBE_WORKERS: usize = 16;
+/// `/proc/net/tcp` 中 LISTEN 状态的编码；UDP 没有监听状态，不做过滤
+#[cfg(target_os = "linux")]
+const PROC_TCP_LISTEN: &str = "0A";
+
+/// 端口相关命令的错误类型，序列化后前端可通过 `type` 字段区分
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+// `Elevation*` 只在 Windows 上构造
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum PortError {
+    CommandSpawnFailed { tool: String, source: String },
+    ToolNotInstalled { tool: String },
+    CommandFailed { tool: String, code: i32 },
+    PermissionDenied { pid: u32 },
+    NoSuchProcess { pid: u32 },
+    ProcessChanged { pid: u32, actual: Option<String> },
+    InvalidPort { port: u16 },
+    InvalidRange { start: u16, end: u16 },
+    NoFreePort { start: u16, end: u16 },
+    RangeTooLarge { start: u16, end: u16, max: u16 },
+    InvalidHost { host: String },
+    WouldKillSelf,
+    ProtectedPid { pid: u32 },
+    TaskFailed { source: String },
+    CommandTimedOut { tool: String, timeout_ms: u64 },
+    SidecarFailed { source: String },
+    PortInUse { port: u16 },
+    PortAlreadyReserved { port: u16 },
+    ReservationNotFound { id: u32 },
+    StoreFailed { source: String },
+    BackendNotConfigured,
+    BackendStopFailed { pid: u32 },
+    BackendNotReady { port: u16, timeout_ms: u64 },
+    BackendNotRunning,
+    BackendStarting { workspace: String },
+    PortNotFreed { port: u16, timeout_ms: u64 },
+    LogFileFailed { source: String },
+    LogFileNotFound { name: String },
+    BackendNotFound { path: String },
+    NotExecutable { path: String },
+    VersionCheckFailed { path: String, reason: String },
+    ConnectionRefused { url: String },
+    HealthTimedOut { url: String, timeout_ms: u64 },
+    Unhealthy { url: String, reason: String },
+    InvalidWorkspace { id: String },
+    InvalidSettings { reason: String },
+    SecretFailed { reason: String },
+    InvalidUrlPath { path: String },
+    OpenUrlFailed { url: String, reason: String },
+    PathNotFound { path: String },
+    ParseMismatch { sample_lines: Vec<String> },
+    FileManagerUnavailable { reason: String },
+    ClipboardFailed { reason: String },
+    ClipboardTooLarge { size: usize, limit: usize },
+    InvalidShortcut { accelerator: String, reason: String },
+    ShortcutConflict { accelerator: String, reason: String },
+    ShortcutsUnsupported,
+    InvalidClipboardData { reason: String },
+    NotificationFailed { reason: String },
+    UnknownWindowKind { kind: String },
+    WindowNotFound { label: String },
+    WindowFailed { reason: String },
+    AutostartUnavailable { reason: String },
+    AutostartFailed { reason: String },
+    ElevationCancelled,
+    ElevationFailed { reason: String },
+    ElevationUnsupported,
+    BackendIncompatible(BackendVersionInfo),
+    StartupTimedOut(StartupFailure),
+    ExitedDuringStartup(StartupFailure),
+    PartialKillFailure(PortsKillFailure),
+}
+
+/// `force_kill_ports` 部分端口失败时的详情：`killed` 为成功的端口上已结束的进程，`errors` 为各失败端口的错误
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortsKillFailure {
+    pub killed: HashMap<u16, Vec<u32>>,
+    pub errors: HashMap<u16, PortError>,
+}
+
+/// 后端未能完成启动时的详情；`stderr` 为本次启动以来的最后若干行输出，通常就是失败原因
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupFailure {
+    pub port: u16,
+    pub timeout_ms: u64,
+    /// 进程在启动中退出时的退出码
+    pub exit_code: Option<i32>,
+    pub stderr: Vec<String>,
+}
+
+impl fmt::Display for PortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CommandSpawnFailed { tool, source } => write!(f, "执行 {tool} 失败: {source}"),
+            Self::ToolNotInstalled { tool } => write!(f, "未找到 {tool} 命令，请先安装后重试"),
+            Self::CommandFailed { tool, code } => write!(f, "{tool} 返回非 0 状态码: {code}"),
+            Self::PermissionDenied { pid } => write!(f, "权限不足，无法结束进程 (PID={pid})"),
+            Self::NoSuchProcess { pid } => write!(f, "进程不存在 (PID={pid})"),
+            Self::ProcessChanged { pid, actual } => write!(
+                f,
+                "进程 {pid} 已变为 {}，PID 可能已被其他程序复用",
+                actual.as_deref().unwrap_or("未知程序")
+            ),
+            Self::InvalidPort { port } => write!(f, "无效的端口号 {port}，应在 1-65535 之间"),
+            Self::InvalidRange { start, end } => write!(f, "端口范围无效: {start}-{end}"),
+            Self::NoFreePort { start, end } => write!(f, "端口范围 {start}-{end} 内没有可用端口"),
+            Self::RangeTooLarge { start, end, max } => {
+                write!(f, "端口范围 {start}-{end} 过大，单次最多扫描 {max} 个端口")
+            }
+            Self::InvalidHost { host } => write!(f, "无效的主机地址: {host}"),
+            Self::WouldKillSelf => write!(f, "端口被当前应用自身占用，已拒绝结束进程"),
+            Self::ProtectedPid { pid } => write!(f, "PID {pid} 是系统关键进程，已拒绝结束"),
+            Self::TaskFailed { source } => write!(f, "后台任务执行失败: {source}"),
+            Self::CommandTimedOut { tool, timeout_ms } => {
+                write!(f, "{tool} 执行超时 ({timeout_ms}ms)")
+            }
+            Self::SidecarFailed { source } => write!(f, "后端进程操作失败: {source}"),
+            Self::PortInUse { port } => write!(f, "端口 {port} 已被占用"),
+            Self::PortAlreadyReserved { port } => write!(f, "端口 {port} 已被预留"),
+            Self::ReservationNotFound { id } => write!(f, "端口预留不存在或已过期 (ID={id})"),
+            Self::StoreFailed { source } => write!(f, "读写设置失败: {source}"),
+            Self::BackendNotConfigured => write!(f, "后端尚未启动过，没有可用于重启的配置"),
+            Self::BackendStopFailed { pid } => write!(f, "无法结束后端进程 (PID={pid})"),
+            Self::BackendNotReady { port, timeout_ms } => {
+                write!(f, "后端在 {timeout_ms}ms 内未开始监听端口 {port}")
+            }
+            Self::BackendNotRunning => write!(f, "后端未在运行"),
+            Self::BackendStarting { workspace } => write!(f, "工作区 {workspace} 的后端正在启动"),
+            Self::PortNotFreed { port, timeout_ms } => {
+                write!(f, "端口 {port} 在 {timeout_ms}ms 内未被释放")
+            }
+            Self::LogFileFailed { source } => write!(f, "读取后端日志失败: {source}"),
+            Self::LogFileNotFound { name } => write!(f, "后端日志文件不存在: {name}"),
+            Self::BackendNotFound { path } => write!(f, "后端程序不存在或不是文件: {path}"),
+            Self::NotExecutable { path } => write!(f, "后端程序不可执行: {path}"),
+            Self::VersionCheckFailed { path, reason } => {
+                write!(f, "无法获取后端程序版本 ({reason}): {path}")
+            }
+            Self::ConnectionRefused { url } => write!(f, "无法连接到后端: {url}"),
+            Self::HealthTimedOut { url, timeout_ms } => {
+                write!(f, "后端健康检查超时 ({timeout_ms}ms): {url}")
+            }
+            Self::Unhealthy { url, reason } => write!(f, "后端状态异常 ({reason}): {url}"),
+            Self::InvalidWorkspace { id } => write!(f, "无效的工作区 ID: {id}"),
+            Self::InvalidSettings { reason } => write!(f, "设置无效: {reason}"),
+            Self::SecretFailed { reason } => write!(f, "读写钥匙串失败: {reason}"),
+            Self::InvalidUrlPath { path } => {
+                write!(f, "无效的路径 {path:?}，应以 / 开头且只包含可见 ASCII 字符")
+            }
+            Self::OpenUrlFailed { url, reason } => write!(f, "无法在浏览器中打开 {url}: {reason}"),
+            Self::PathNotFound { path } => write!(f, "文件或目录不存在: {path}"),
+            Self::ParseMismatch { .. } => {
+                write!(
+                    f,
+                    "端口已被占用，但无法从命令输出中找到占用进程，输出格式可能与预期不符"
+                )
+            }
+            Self::FileManagerUnavailable { reason } => {
+                write!(f, "无法在文件管理器中显示: {reason}")
+            }
+            Self::ClipboardFailed { reason } => write!(f, "读写剪贴板失败: {reason}"),
+            Self::ClipboardTooLarge { size, limit } => {
+                write!(f, "剪贴板数据过大（{size} 字节），上限为 {limit} 字节")
+            }
+            Self::InvalidClipboardData { reason } => write!(f, "剪贴板数据无效: {reason}"),
+            Self::InvalidShortcut {
+                accelerator,
+                reason,
+            } => write!(f, "无效的快捷键 {accelerator}: {reason}"),
+            Self::ShortcutConflict {
+                accelerator,
+                reason,
+            } => write!(
+                f,
+                "无法注册快捷键 {accelerator}，可能已被系统或其他程序占用: {reason}"
+            ),
+            Self::ShortcutsUnsupported => write!(f, "当前平台不支持全局快捷键"),
+            Self::NotificationFailed { reason } => write!(f, "发送系统通知失败: {reason}"),
+            Self::UnknownWindowKind { kind } => write!(f, "未知的窗口类别: {kind}"),
+            Self::WindowNotFound { label } => write!(f, "窗口不存在: {label}"),
+            Self::WindowFailed { reason } => write!(f, "窗口操作失败: {reason}"),
+            Self::AutostartUnavailable { reason } => write!(f, "无法设置开机自启动: {reason}"),
+            Self::AutostartFailed { reason } => write!(f, "修改开机自启动失败: {reason}"),
+            Self::ElevationCancelled => write!(f, "已取消以管理员身份重新启动"),
+            Self::ElevationFailed { reason } => write!(f, "以管理员身份重新启动失败: {reason}"),
+            Self::ElevationUnsupported => write!(f, "当前平台不支持以管理员身份重新启动"),
+            Self::BackendIncompatible(info) => write!(
+                f,
+                "后端版本 {} 过旧，至少需要 {}，请重新安装应用",
+                info.version.as_deref().unwrap_or("未知"),
+                info.minimum
+            ),
+            Self::StartupTimedOut(failure) => write!(
+                f,
+                "后端在 {}ms 内未就绪 (端口 {})",
+                failure.timeout_ms, failure.port
+            ),
+            Self::ExitedDuringStartup(failure) => match failure.exit_code {
+                Some(code) => write!(f, "后端在启动过程中退出，退出码 {code}"),
+                None => write!(f, "后端在启动过程中退出"),
+            },
+            Self::PartialKillFailure(failure) => {
+                let mut ports: Vec<u16> = failure.errors.keys().copied().collect();
+                ports.sort_unstable();
+                let ports: Vec<String> = ports.iter().map(u16::to_string).collect();
+                write!(f, "部分端口上的进程未能结束: {}", ports.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for PortError {}
+
+fn spawn_failed(tool: &str, error: std::io::Error) -> PortError {
+    // 精简的 Linux 发行版 / 容器中常缺少 lsof，单独报告以便界面提示安装
+    if error.kind() == std::io::ErrorKind::NotFound {
+        return PortError::ToolNotInstalled {
+            tool: tool.to_string(),
+        };
+    }
+    PortError::CommandSpawnFailed {
+        tool: tool.to_string(),
+        source: error.to_string(),
+    }
+}
+
+pub(crate) fn command_failed(tool: &str, status: std::process::ExitStatus) -> PortError {
+    PortError::CommandFailed {
+        tool: tool.to_string(),
+        code: status.code().unwrap_or(-1),
+    }
+}
+
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+fn wait_with_timeout(tool: &str, mut child: Child, timeout: Duration) -> Result<Output, PortError> {
+    // 在独立线程中读取输出，避免管道写满导致子进程阻塞
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    let deadline = std::time::Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait().map_err(|e| spawn_failed(tool, e))? {
+            Some(status) => break status,
+            None if std::time::Instant::now() >= deadline => {
+                warn!(tool, "外部命令超时，已结束");
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(PortError::CommandTimedOut {
+                    tool: tool.to_string(),
+                    timeout_ms: timeout.as_millis() as u64,
+                });
+            }
+            None => thread::sleep(TOOL_POLL_INTERVAL),
+        }
+    };
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// 执行外部命令并收集输出，超过 `TOOL_TIMEOUT` 仍未结束时强制结束该命令
+pub(crate) fn run_tool(tool: &str, args: &[&str]) -> Result<Output, PortError> {
+    run_tool_with_timeout(tool, args, TOOL_TIMEOUT)
+}
+
+/// 同 `run_tool`，但非零状态退出且没有任何输出时视为瞬时失败，最多执行 `attempts` 次；
+/// 有输出的结果无论成功与否都原样返回，由调用方解析
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn run_with_retry(
+    tool: &str,
+    args: &[&str],
+    attempts: u32,
+) -> Result<Output, PortError> {
+    retry_transient(tool, attempts, TOOL_RETRY_BACKOFF, || run_tool(tool, args))
+}
+
+/// `run_with_retry` 的重试循环：第 n 次失败后等待 `backoff * n` 再执行 `run`，启动失败等错误不重试
+fn retry_transient(
+    tool: &str,
+    attempts: u32,
+    backoff: Duration,
+    mut run: impl FnMut() -> Result<Output, PortError>,
+) -> Result<Output, PortError> {
+    let mut attempt = 1;
+    loop {
+        let output = run()?;
+        let transient = !output.status.success() && output.stdout.is_empty();
+        if !transient || attempt >= attempts {
+            return Ok(output);
+        }
+        debug!(tool, attempt, "命令失败且没有输出，稍后重试");
+        thread::sleep(backoff * attempt);
+        attempt += 1;
+    }
+}
+
+pub(crate) fn run_tool_with_timeout(
+    tool: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<Output, PortError> {
+    let child = Command::new(tool)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| spawn_failed(tool, e))?;
+    let output = wait_with_timeout(tool, child, timeout)?;
+    debug!(
+        tool,
+        ?args,
+        code = ?output.status.code(),
+        stdout_len = output.stdout.len(),
+        "外部命令已退出"
+    );
+    Ok(output)
+}
+
+/// 端口占用探测结果，按地址族区分占用情况
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortUsage {
+    pub port: u16,
+    pub in_use: bool,
+    pub ipv4_in_use: bool,
+    pub ipv6_in_use: bool,
+    /// 探测过的全部地址，按探测顺序排列
+    pub checked: Vec<String>,
+    /// 绑定失败的地址，例如 `127.0.0.1:5000`、`[::1]:5000`
+    pub occupied: Vec<String>,
+}
+
+/// 端口协议，前端传入 `"tcp"` / `"udp"`，缺省为 TCP
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+/// 未指定 host 时探测的地址范围，前端传入 `"loopback"` / `"allInterfaces"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BindScope {
+    /// 只探测 `127.0.0.1` / `::1`
+    Loopback,
+    /// 只探测 `0.0.0.0` / `::`，对应后端监听所有网卡供局域网访问的场景。
+    /// macOS 上绑定通配地址可能弹出防火墙授权提示
+    AllInterfaces,
+}
+
+/// 探测的地址族，前端传入 `"v4"` / `"v6"` / `"both"`，缺省为两者
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFamily {
+    V4,
+    V6,
+    #[default]
+    Both,
+}
+
+impl AddressFamily {
+    fn includes(self, ip: &IpAddr) -> bool {
+        match self {
+            Self::V4 => ip.is_ipv4(),
+            Self::V6 => ip.is_ipv6(),
+            Self::Both => true,
+        }
+    }
+}
+
+/// 解析调用方指定的主机：IP 地址（IPv6 可带方括号）直接使用，其他按主机名解析（如 `localhost`，
+/// 结果可能同时包含 IPv4 与 IPv6 地址）。无法解析时返回 `InvalidHost`；解析可能阻塞，需在阻塞线程池中调用
+pub(crate) fn resolve_host(host: &str) -> Result<Vec<IpAddr>, PortError> {
+    let invalid = || PortError::InvalidHost {
+        host: host.to_string(),
+    };
+    let bare = host.trim().trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = bare.parse::<IpAddr>() {
+        return Ok(vec![ip]);
+    }
+    if bare.is_empty() {
+        return Err(invalid());
+    }
+    let mut ips = Vec::new();
+    for addr in (bare, 0).to_socket_addrs().map_err(|_| invalid())? {
+        if !ips.contains(&addr.ip()) {
+            ips.push(addr.ip());
+        }
+    }
+    if ips.is_empty() {
+        return Err(invalid());
+    }
+    Ok(ips)
+}
+
+/// 显式指定的 `host` 优先于 `scope` 与 `family`；都未指定时同时探测回环与通配地址
+pub(crate) fn probe_hosts(
+    host: Option<&str>,
+    scope: Option<BindScope>,
+    family: AddressFamily,
+) -> Result<Vec<IpAddr>, PortError> {
+    match host.map(str::trim).filter(|host| !host.is_empty()) {
+        Some(host) => resolve_host(host),
+        None => {
+            let hosts: &[IpAddr] = match scope {
+                Some(BindScope::Loopback) => &LOOPBACK_HOSTS,
+                Some(BindScope::AllInterfaces) => &WILDCARD_HOSTS,
+                None => &DEFAULT_PROBE_HOSTS,
+            };
+            Ok(hosts
+                .iter()
+                .copied()
+                .filter(|ip| family.includes(ip))
+                .collect())
+        }
+    }
+}
+
+fn bind_conflicts(addr: SocketAddr, protocol: Protocol) -> bool {
+    let bound = match protocol {
+        Protocol::Tcp => TcpListener::bind(addr).map(drop),
+        Protocol::Udp => UdpSocket::bind(addr).map(drop),
+    };
+    match bound {
+        Ok(()) => false,
+        // 本机未启用该地址（如禁用了 IPv6）时不视为占用
+        Err(e) => e.kind() != ErrorKind::AddrNotAvailable,
+    }
+}
+
+pub(crate) fn port_usage(port: u16, hosts: &[IpAddr], protocol: Protocol) -> PortUsage {
+    let mut usage = PortUsage {
+        port,
+        in_use: false,
+        ipv4_in_use: false,
+        ipv6_in_use: false,
+        checked: Vec::new(),
+        occupied: Vec::new(),
+    };
+    for ip in hosts {
+        let addr = SocketAddr::new(*ip, port);
+        usage.checked.push(addr.to_string());
+        if bind_conflicts(addr, protocol) {
+            usage.in_use = true;
+            match ip {
+                IpAddr::V4(_) => usage.ipv4_in_use = true,
+                IpAddr::V6(_) => usage.ipv6_in_use = true,
+            }
+            usage.occupied.push(addr.to_string());
+        }
+    }
+    usage
+}
+
+/// 命令入口统一调用：绑定端口 0 会得到系统分配的临时端口，探测结果没有意义；
+/// 大于 65535 的取值在反序列化为 `u16` 时就已被拒绝
+pub(crate) fn validate_port(port: u16) -> Result<(), PortError> {
+    if port == 0 {
+        return Err(PortError::InvalidPort { port });
+    }
+    Ok(())
+}
+
+/// 所有默认地址都能绑定 TCP 才视为空闲，监听器在返回前立即释放
+pub(crate) fn can_bind(port: u16) -> bool {
+    !port_usage(port, &DEFAULT_PROBE_HOSTS, Protocol::Tcp).in_use
+}
+
+/// 在有限的线程数内并发探测端口，结果与 `ports` 顺序一致
+pub(crate) fn probe_concurrently<T, F>(ports: &[u16], probe: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(u16) -> T + Sync,
+{
+    if ports.is_empty() {
+        return Vec::new();
+    }
+    let chunk_size = ports.len().div_ceil(PROBE_WORKERS);
+    let probe = &probe;
+    thread::scope(|scope| {
+        let workers: Vec<_> = ports
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || chunk.iter().map(|&port| probe(port)).collect::<Vec<_>>())
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+/// 批量检查 TCP 端口占用，结果与输入一一对应；重复的端口只探测一次
+pub(crate) fn probe_many(ports: &[u16]) -> Vec<bool> {
+    let mut unique = ports.to_vec();
+    unique.sort_unstable();
+    unique.dedup();
+    let in_use = probe_concurrently(&unique, |port| !can_bind(port));
+    ports
+        .iter()
+        .map(|port| unique.binary_search(port).is_ok_and(|index| in_use[index]))
+        .collect()
+}
+
+/// `wait_for_port` 等待的目标状态
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PortState {
+    /// 端口可以建立连接，即服务已开始监听
+    Open,
+    /// 端口可以重新绑定，即原进程已释放
+    Free,
+}
+
+/// 能否连上本机端口；比绑定探测更能说明服务已就绪
+pub(crate) async fn accepts_connections(port: u16) -> bool {
+    connects_to_any(&LOOPBACK_HOSTS, port).await
+}
+
+/// 能否连上 `hosts` 中任一地址的 `port`
+async fn connects_to_any(hosts: &[IpAddr], port: u16) -> bool {
+    for &ip in hosts {
+        let attempt = TcpStream::connect(SocketAddr::new(ip, port));
+        if matches!(timeout(CONNECT_ATTEMPT_TIMEOUT, attempt).await, Ok(Ok(_))) {
+            return true;
+        }
+    }
+    false
+}
+
+/// 能否在 `wait` 内连上 `host:port`；`host` 可以是主机名或带方括号的 IPv6 地址。
+/// 主机名先在 `RESOLVE_TIMEOUT` 内解析，无法解析时返回 `false`；`wait` 只用于依次连接解析出的各个地址
+pub(crate) async fn can_connect_to(host: &str, port: u16, wait: Duration) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let Ok(Ok(addrs)) = timeout(RESOLVE_TIMEOUT, lookup_host((host, port))).await else {
+        return false;
+    };
+    let addrs: Vec<SocketAddr> = addrs.collect();
+    let connect = async {
+        for addr in addrs {
+            if TcpStream::connect(addr).await.is_ok() {
+                return true;
+            }
+        }
+        false
+    };
+    timeout(wait, connect).await.unwrap_or(false)
+}
+
+/// 轮询直到端口达到目标状态，超时返回 `false`
+pub(crate) async fn wait_for_state(
+    port: u16,
+    state: PortState,
+    timeout: Duration,
+    interval: Duration,
+) -> bool {
+    let cancel = CancellationToken::new();
+    wait_for_state_cancellable(port, None, state, timeout, interval, &cancel).await
+}
+
+/// 同 `wait_for_state`，`cancel` 被触发时立即返回 `false`；指定 `hosts` 时只探测这些地址，
+/// 否则探测本机的回环（`Open`）或回环与通配地址（`Free`）
+pub(crate) async fn wait_for_state_cancellable(
+    port: u16,
+    hosts: Option<&[IpAddr]>,
+    state: PortState,
+    wait_timeout: Duration,
+    interval: Duration,
+    cancel: &CancellationToken,
+) -> bool {
+    let deadline = Instant::now() + wait_timeout;
+    loop {
+        if cancel.is_cancelled() {
+            return false;
+        }
+        let reached = match (state, hosts) {
+            (PortState::Open, Some(hosts)) => connects_to_any(hosts, port).await,
+            (PortState::Open, None) => accepts_connections(port).await,
+            (PortState::Free, Some(hosts)) => !port_usage(port, hosts, Protocol::Tcp).in_use,
+            (PortState::Free, None) => can_bind(port),
+        };
+        if reached {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        // 在轮询间隔内等待取消信号，超时说明未被取消，继续下一轮
+        if timeout(interval, cancel.cancelled()).await.is_ok() {
+            return false;
+        }
+    }
+}
+
+/// 常被其他开发工具与数据库占用的端口；自动分配端口时跳过，避免刚分配就与随后启动的程序冲突
+pub(crate) const COMMON_DEV_PORTS: &[u16] = &[
+    3000, 3001, 3306, 4200, 5173, 5432, 6379, 8000, 8080, 8081, 8443, 8888, 9000, 9229, 27017,
+];
+
+/// 在闭区间 `[start, end]` 内查找第一个可绑定且不在 `exclude` 中的端口，`start > end` 时返回 `None`。
+/// `exclude` 中的端口在扫描时直接跳过，不会尝试绑定
+pub(crate) fn find_free_port_excluding(start: u16, end: u16, exclude: &[u16]) -> Option<u16> {
+    if start > end {
+        return None;
+    }
+    (start..=end)
+        .filter(|port| !exclude.contains(port))
+        .find(|port| can_bind(*port))
+}
+
+fn push_unique(pids: &mut Vec<u32>, pid: u32) {
+    if !pids.contains(&pid) {
+        pids.push(pid);
+    }
+}
+
+/// 将 netstat 的本地地址列拆分为主机与端口，支持 `0.0.0.0:5000` 与 `[::]:5000` 两种形式
+#[cfg_attr(not(windows), allow(dead_code))]
+fn split_local_addr(local_addr: &str) -> Option<(&str, u16)> {
+    let (host, port) = local_addr.rsplit_once(':')?;
+    let host = match host.strip_prefix('[') {
+        Some(inner) => inner.strip_suffix(']')?,
+        None if host.contains(':') => return None,
+        None => host,
+    };
+    Some((host, port.parse().ok()?))
+}
+
+/// 解析 `netstat -ano` 输出，返回所有监听（UDP 为绑定）的 `(端口, PID)`，保持出现顺序；
+/// IPv4 与 IPv6 共用 `TCP` / `UDP` 协议名。TCP 只取 LISTENING 行；
+/// UDP 行没有状态列（`UDP 0.0.0.0:5353 *:* 1234`），PID 位于第 4 列。
+/// 列数不足或 PID 非数字的行直接跳过
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_netstat_sockets(stdout: &str, protocol: Protocol) -> Vec<(u16, u32)> {
+    let proto_name = match protocol {
+        Protocol::Tcp => "TCP",
+        Protocol::Udp => "UDP",
+    };
+    let mut sockets = Vec::new();
+
+    for line in stdout.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() < 4 || !columns[0].eq_ignore_ascii_case(proto_name) {
+            continue;
+        }
+
+        let pid_column = match protocol {
+            Protocol::Tcp if columns.len() < 5 || !columns[3].eq_ignore_ascii_case("LISTENING") => {
+                continue
+            }
+            Protocol::Tcp => columns[4],
+            Protocol::Udp => columns[3],
+        };
+        let Ok(pid) = pid_column.parse::<u32>() else {
+            continue;
+        };
+
+        if let Some((_, local_port)) = split_local_addr(columns[1]) {
+            sockets.push((local_port, pid));
+        }
+    }
+
+    sockets
+}
+
+/// 解析 `netstat -ano` 输出，返回占用指定端口的 PID（去重，保持出现顺序）
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_netstat_listeners(stdout: &str, port: u16, protocol: Protocol) -> Vec<u32> {
+    let mut pids = Vec::new();
+    // 按数值比较端口，避免 `:80` 误匹配 `:8080`
+    for (_, pid) in parse_netstat_sockets(stdout, protocol)
+        .into_iter()
+        .filter(|(local_port, _)| *local_port == port)
+    {
+        push_unique(&mut pids, pid);
+    }
+    pids
+}
+
+/// 解析 `lsof -t` 输出（每行一个 PID），非数字行直接跳过
+#[cfg_attr(windows, allow(dead_code))]
+fn parse_lsof_pids(stdout: &str) -> Vec<u32> {
+    let mut pids = Vec::new();
+    for pid in stdout
+        .lines()
+        .filter_map(|line| line.trim().parse::<u32>().ok())
+    {
+        push_unique(&mut pids, pid);
+    }
+    pids
+}
+
+/// 解析 `lsof -F pn` 输出：`p<PID>` 行开始一个进程，其后的 `n<地址>:<端口>` 行为它打开的 socket
+#[cfg_attr(windows, allow(dead_code))]
+fn parse_lsof_listeners(stdout: &str) -> Vec<(u16, u32)> {
+    let mut pid = None;
+    let mut sockets = Vec::new();
+    for line in stdout.lines() {
+        if let Some(value) = line.strip_prefix('p') {
+            pid = value.parse::<u32>().ok();
+        } else if let (Some(name), Some(pid)) = (line.strip_prefix('n'), pid) {
+            if let Some(port) = name
+                .rsplit_once(':')
+                .and_then(|(_, port)| port.parse::<u16>().ok())
+            {
+                sockets.push((port, pid));
+            }
+        }
+    }
+    sockets
+}
+
+/// `ParseMismatch` 附带的原始输出行数
+const PARSE_SAMPLE_LINES: usize = 5;
+
+/// 外部命令有输出却没有解析出占用 `port` 的进程：优先附带提到该端口的行，没有时附带开头几行
+fn parse_mismatch(stdout: &str, port: u16) -> PortError {
+    let lines = || {
+        stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+    };
+    // 端口号之后不能紧跟数字，否则 `:80` 会匹配到 `:8080`
+    let needle = format!(":{port}");
+    let mentions_port = |line: &str| {
+        line.match_indices(&needle)
+            .any(|(at, _)| !line[at + needle.len()..].starts_with(|c: char| c.is_ascii_digit()))
+    };
+    let mut sample_lines: Vec<String> = lines()
+        .filter(|line| mentions_port(line))
+        .take(PARSE_SAMPLE_LINES)
+        .map(String::from)
+        .collect();
+    if sample_lines.is_empty() {
+        sample_lines = lines().take(PARSE_SAMPLE_LINES).map(String::from).collect();
+    }
+    PortError::ParseMismatch { sample_lines }
+}
+
+#[cfg(target_os = "windows")]
+fn tcp_listeners_from_tool() -> Result<Vec<(u16, u32)>, PortError> {
+    let output = run_with_retry("netstat", &["-ano"], NETSTAT_ATTEMPTS)?;
+    if !output.status.success() {
+        return Err(command_failed("netstat", output.status));
+    }
+    Ok(parse_netstat_sockets(
+        &String::from_utf8_lossy(&output.stdout),
+        Protocol::Tcp,
+    ))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn tcp_listeners_from_tool() -> Result<Vec<(u16, u32)>, PortError> {
+    let output = run_tool("lsof", &["-nP", "-iTCP", "-sTCP:LISTEN", "-F", "pn"])?;
+    // 没有任何监听时 lsof 以非零状态退出且没有输出
+    if !output.status.success() && output.stdout.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !output.status.success() {
+        return Err(command_failed("lsof", output.status));
+    }
+    Ok(parse_lsof_listeners(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+#[cfg(target_os = "windows")]
+fn listening_pids_from_tool(port: u16, protocol: Protocol) -> Result<Vec<u32>, PortError> {
+    // `-p tcp` 只列出 IPv4 连接，这里不限定协议以同时覆盖 IPv6 监听
+    let output = run_with_retry("netstat", &["-ano"], NETSTAT_ATTEMPTS)?;
+    if !output.status.success() {
+        return Err(command_failed("netstat", output.status));
+    }
+
+    netstat_pids_or_mismatch(
+        &String::from_utf8_lossy(&output.stdout),
+        port,
+        protocol,
+        || port_usage(port, &DEFAULT_PROBE_HOSTS, protocol).in_use,
+    )
+}
+
+/// 从 netstat 输出中取占用 `port` 的 PID。本地化或旧版系统的 netstat 列布局不同时所有行都会被跳过；
+/// 没有解析出 PID 而 `in_use` 确认端口被占用时返回 `ParseMismatch`，而不是当作无人占用
+#[cfg_attr(not(windows), allow(dead_code))]
+fn netstat_pids_or_mismatch(
+    stdout: &str,
+    port: u16,
+    protocol: Protocol,
+    in_use: impl FnOnce() -> bool,
+) -> Result<Vec<u32>, PortError> {
+    let pids = parse_netstat_listeners(stdout, port, protocol);
+    if pids.is_empty() && in_use() {
+        return Err(parse_mismatch(stdout, port));
+    }
+    Ok(pids)
+}
+
+/// 列出监听 TCP `port` 的 PID 的 lsof 参数；只取 LISTEN 状态，连接到该端口的客户端进程不算占用方。
+/// 此前的 `lsof -ti tcp:{port}` 还会返回已建立连接的一方，结束占用进程时可能误杀连着后端的浏览器
+#[cfg_attr(windows, allow(dead_code))]
+fn lsof_listen_args(port: u16) -> Vec<String> {
+    ["-nP", "-t", &format!("-iTCP:{port}"), "-sTCP:LISTEN"]
+        .map(String::from)
+        .to_vec()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn listening_pids_from_tool(port: u16, protocol: Protocol) -> Result<Vec<u32>, PortError> {
+    let output = match protocol {
+        Protocol::Tcp => {
+            let args = lsof_listen_args(port);
+            run_tool("lsof", &args.iter().map(String::as_str).collect::<Vec<_>>())
+        }
+        Protocol::Udp => run_tool("lsof", &["-nP", "-t", &format!("-iUDP:{port}")]),
+    }?;
+
+    if !output.status.success() && output.stdout.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    if !output.status.success() {
+        return Err(command_failed("lsof", output.status));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let pids = parse_lsof_pids(&stdout);
+    if pids.is_empty() && !stdout.trim().is_empty() {
+        return Err(parse_mismatch(&stdout, port));
+    }
+    Ok(pids)
+}
+
+/// Windows 下通过 `GetExtendedTcpTable` / `GetExtendedUdpTable` 读取 socket 表，
+/// 不依赖 netstat 输出格式与系统语言
+#[cfg(target_os = "windows")]
+fn native_listening_pids(port: u16, protocol: Protocol) -> Option<Vec<u32>> {
+    let protocol_flags = match protocol {
+        Protocol::Tcp => ProtocolFlags::TCP,
+        Protocol::Udp => ProtocolFlags::UDP,
+    };
+    let sockets = get_sockets_info(
+        AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6,
+        protocol_flags,
+    )
+    .ok()?;
+
+    let mut pids = Vec::new();
+    for socket in sockets {
+        let occupies_port = match &socket.protocol_socket_info {
+            ProtocolSocketInfo::Tcp(tcp) => {
+                tcp.local_port == port && matches!(tcp.state, TcpState::Listen)
+            }
+            ProtocolSocketInfo::Udp(udp) => udp.local_port == port,
+        };
+        if occupies_port {
+            for pid in &socket.associated_pids {
+                push_unique(&mut pids, *pid);
+            }
+        }
+    }
+    Some(pids)
+}
+
+#[cfg(target_os = "windows")]
+fn native_tcp_listeners() -> Option<Vec<(u16, u32)>> {
+    let sockets = get_sockets_info(
+        AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6,
+        ProtocolFlags::TCP,
+    )
+    .ok()?;
+    Some(
+        sockets
+            .into_iter()
+            .filter_map(|socket| match socket.protocol_socket_info {
+                ProtocolSocketInfo::Tcp(tcp) if matches!(tcp.state, TcpState::Listen) => {
+                    Some((tcp.local_port, socket.associated_pids))
+                }
+                _ => None,
+            })
+            .flat_map(|(port, pids)| pids.into_iter().map(move |pid| (port, pid)))
+            .collect(),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn native_listening_pids(port: u16, protocol: Protocol) -> Option<Vec<u32>> {
+    linux_listeners_from_proc(port, protocol).ok()
+}
+
+#[cfg(target_os = "linux")]
+fn native_tcp_listeners() -> Option<Vec<(u16, u32)>> {
+    linux_tcp_listeners_from_proc().ok()
+}
+
+/// macOS 没有 `/proc`，直接使用 lsof
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn native_listening_pids(_port: u16, _protocol: Protocol) -> Option<Vec<u32>> {
+    None
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn native_tcp_listeners() -> Option<Vec<(u16, u32)>> {
+    None
+}
+
+/// 读取 `/proc/net/{tcp,udp}{,6}` 中占用端口的 socket inode，再扫描 `/proc/<pid>/fd` 找到持有者
+#[cfg(target_os = "linux")]
+fn linux_listeners_from_proc(port: u16, protocol: Protocol) -> io::Result<Vec<u32>> {
+    let inodes: Vec<u64> = proc_net_sockets(protocol)?
+        .into_iter()
+        .filter(|(local_port, _)| *local_port == port)
+        .map(|(_, inode)| inode)
+        .collect();
+    if inodes.is_empty() {
+        return Ok(Vec::new());
+    }
+    pids_owning_sockets(&inodes)
+}
+
+/// 所有 TCP 监听 socket 的 `(端口, PID)`；找不到持有者的 socket（其他用户的进程）被跳过
+#[cfg(target_os = "linux")]
+fn linux_tcp_listeners_from_proc() -> io::Result<Vec<(u16, u32)>> {
+    let sockets = proc_net_sockets(Protocol::Tcp)?;
+    let inodes: Vec<u64> = sockets.iter().map(|(_, inode)| *inode).collect();
+    let owners = socket_owners(&inodes)?;
+    Ok(sockets
+        .iter()
+        .flat_map(|(port, inode)| {
+            owners
+                .iter()
+                .filter(move |(owned, _)| owned == inode)
+                .map(move |(_, pid)| (*port, *pid))
+        })
+        .collect())
+}
+
+/// 读取 IPv4 与 IPv6 的 socket 表，返回监听（UDP 为绑定）socket 的 `(端口, inode)`
+#[cfg(target_os = "linux")]
+fn proc_net_sockets(protocol: Protocol) -> io::Result<Vec<(u16, u64)>> {
+    let tables = match protocol {
+        Protocol::Tcp => ["/proc/net/tcp", "/proc/net/tcp6"],
+        Protocol::Udp => ["/proc/net/udp", "/proc/net/udp6"],
+    };
+    let mut sockets = Vec::new();
+    for table in tables {
+        match fs::read_to_string(table) {
+            Ok(content) => sockets.extend(parse_proc_net_sockets(&content, protocol)),
+            // 未启用 IPv6 时没有 tcp6 / udp6
+            Err(e) if e.kind() == ErrorKind::NotFound && table.ends_with('6') => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(sockets)
+}
+
+/// 解析 `/proc/net/tcp` 格式的 socket 表（UDP 表格式相同），返回 `(本地端口, inode)`；
+/// TCP 只取状态为 `0A`（LISTEN）的行
+#[cfg(target_os = "linux")]
+fn parse_proc_net_sockets(content: &str, protocol: Protocol) -> Vec<(u16, u64)> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() < 10 {
+                return None;
+            }
+            if protocol == Protocol::Tcp && columns[3] != PROC_TCP_LISTEN {
+                return None;
+            }
+            let (_, port_hex) = columns[1].rsplit_once(':')?;
+            let port = u16::from_str_radix(port_hex, 16).ok()?;
+            let inode = columns[9].parse::<u64>().ok().filter(|inode| *inode != 0)?;
+            Some((port, inode))
+        })
+        .collect()
+}
+
+/// 持有指定 socket 的进程，去重并保持 `/proc` 中的顺序
+#[cfg(target_os = "linux")]
+fn pids_owning_sockets(inodes: &[u64]) -> io::Result<Vec<u32>> {
+    let mut pids = Vec::new();
+    for (_, pid) in socket_owners(inodes)? {
+        push_unique(&mut pids, pid);
+    }
+    Ok(pids)
+}
+
+/// 扫描 `/proc/<pid>/fd`，返回持有 `inodes` 中 socket 的 `(inode, PID)`；
+/// 无权读取 fd 目录的进程（其他用户）会被跳过
+#[cfg(target_os = "linux")]
+fn socket_owners(inodes: &[u64]) -> io::Result<Vec<(u64, u32)>> {
+    let mut owners = Vec::new();
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|target| target.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok());
+            if let Some(inode) = inode.filter(|inode| inodes.contains(inode)) {
+                owners.push((inode, pid));
+            }
+        }
+    }
+    Ok(owners)
+}
+
+/// 本机所有 TCP 监听的 `(端口, PID)`，按端口号升序；同一进程在 IPv4 与 IPv6 上监听同一端口只保留一项。
+/// 与 `pids_listening_on` 一样优先读取系统 socket 表
+pub(crate) fn tcp_listeners() -> Result<Vec<(u16, u32)>, PortError> {
+    let mut listeners = match native_tcp_listeners() {
+        Some(listeners) => listeners,
+        None => {
+            debug!("系统 socket 表不可用，回退到外部命令");
+            tcp_listeners_from_tool()?
+        }
+    };
+    listeners.sort_unstable();
+    listeners.dedup();
+    Ok(listeners)
+}
+
+/// 查找监听指定端口（UDP 为绑定）的 PID：优先读取系统 socket 表，不可用时回退到 netstat / lsof
+pub(crate) fn pids_listening_on(port: u16, protocol: Protocol) -> Result<Vec<u32>, PortError> {
+    let pids = match native_listening_pids(port, protocol) {
+        Some(pids) => pids,
+        None => {
+            debug!(port, ?protocol, "系统 socket 表不可用，回退到外部命令");
+            listening_pids_from_tool(port, protocol)?
+        }
+    };
+    debug!(port, ?protocol, ?pids, "已解析端口占用进程");
+    Ok(pids)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// 经过前端实际收到的 JSON 文本再解析回来，检查 `type` 标签与字段名
+    fn wire(error: &PortError) -> Value {
+        let text = serde_json::to_string(error).unwrap();
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn port_error_serializes_with_type_tag() {
+        let cases = [
+            (
+                PortError::CommandSpawnFailed {
+                    tool: "lsof".into(),
+                    source: "boom".into(),
+                },
+                json!({ "type": "CommandSpawnFailed", "tool": "lsof", "source": "boom" }),
+            ),
+            (
+                PortError::CommandFailed {
+                    tool: "netstat".into(),
+                    code: 1,
+                },
+                json!({ "type": "CommandFailed", "tool": "netstat", "code": 1 }),
+            ),
+            (
+                PortError::PermissionDenied { pid: 42 },
+                json!({ "type": "PermissionDenied", "pid": 42 }),
+            ),
+            (
+                PortError::NoSuchProcess { pid: 7 },
+                json!({ "type": "NoSuchProcess", "pid": 7 }),
+            ),
+            (PortError::WouldKillSelf, json!({ "type": "WouldKillSelf" })),
+            (
+                PortError::CommandTimedOut {
+                    tool: "lsof".into(),
+                    timeout_ms: 10_000,
+                },
+                json!({ "type": "CommandTimedOut", "tool": "lsof", "timeout_ms": 10_000 }),
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(wire(&error), expected, "{error}");
+        }
+    }
+
+    #[test]
+    fn port_error_flattens_newtype_payloads() {
+        let error = PortError::StartupTimedOut(StartupFailure {
+            port: 5000,
+            timeout_ms: 30_000,
+            exit_code: None,
+            stderr: vec!["address in use".into()],
+        });
+        assert_eq!(
+            wire(&error),
+            json!({
+                "type": "StartupTimedOut",
+                "port": 5000,
+                "timeoutMs": 30_000,
+                "exitCode": null,
+                "stderr": ["address in use"],
+            })
+        );
+    }
+
+    /// 系统分配的临时端口，监听器保持打开
+    fn bound_tcp(ip: IpAddr) -> Option<(TcpListener, u16)> {
+        let listener = TcpListener::bind(SocketAddr::new(ip, 0)).ok()?;
+        let port = listener.local_addr().ok()?.port();
+        Some((listener, port))
+    }
+
+    #[test]
+    fn find_free_port_skips_bound_port() {
+        let (_listener, port) = bound_tcp(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        assert_eq!(find_free_port_excluding(port, port, &[]), None);
+        if let Some(end) = port.checked_add(20) {
+            let found = find_free_port_excluding(port, end, &[]).unwrap();
+            assert!(found > port && found <= end);
+        }
+    }
+
+    #[test]
+    fn find_free_port_honours_exclude_and_empty_range() {
+        assert_eq!(find_free_port_excluding(2, 1, &[]), None);
+        let (listener, port) = bound_tcp(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        drop(listener);
+        assert_eq!(find_free_port_excluding(port, port, &[port]), None);
+    }
+
+    #[test]
+    fn ipv6_only_listener_counts_as_in_use() {
+        // 未启用 IPv6 的环境无法构造该场景
+        let Some((_listener, port)) = bound_tcp(IpAddr::V6(Ipv6Addr::LOCALHOST)) else {
+            return;
+        };
+        let usage = port_usage(port, &DEFAULT_PROBE_HOSTS, Protocol::Tcp);
+        assert!(usage.in_use);
+        assert!(usage.ipv6_in_use);
+        assert!(!usage.ipv4_in_use);
+        assert!(usage.occupied.contains(&format!("[::1]:{port}")));
+        assert!(!can_bind(port));
+        assert_eq!(find_free_port_excluding(port, port, &[]), None);
+    }
+
+    /// 在回环地址上占用一个 TCP 或 UDP 端口，地址族不可用时返回 `None`
+    fn bound_socket(protocol: Protocol, ip: IpAddr) -> Option<(Box<dyn std::any::Any>, u16)> {
+        let addr = SocketAddr::new(ip, 0);
+        match protocol {
+            Protocol::Tcp => {
+                let listener = TcpListener::bind(addr).ok()?;
+                let port = listener.local_addr().ok()?.port();
+                Some((Box::new(listener), port))
+            }
+            Protocol::Udp => {
+                let socket = UdpSocket::bind(addr).ok()?;
+                let port = socket.local_addr().ok()?.port();
+                Some((Box::new(socket), port))
+            }
+        }
+    }
+
+    #[test]
+    fn port_usage_lists_every_probed_address() {
+        let (listener, port) = bound_tcp(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        drop(listener);
+        let usage = port_usage(port, &LOOPBACK_HOSTS, Protocol::Tcp);
+        assert_eq!(
+            usage.checked,
+            vec![format!("127.0.0.1:{port}"), format!("[::1]:{port}")]
+        );
+        let usage = port_usage(port, &DEFAULT_PROBE_HOSTS, Protocol::Tcp);
+        assert!(usage.checked.contains(&format!("127.0.0.1:{port}")));
+        assert!(usage.checked.contains(&format!("[::1]:{port}")));
+        assert_eq!(usage.checked.len(), DEFAULT_PROBE_HOSTS.len());
+    }
+
+    #[test]
+    fn port_usage_matrix_by_protocol_family_and_scope() {
+        let families = [AddressFamily::V4, AddressFamily::V6, AddressFamily::Both];
+        let scopes = [
+            (BindScope::Loopback, LOOPBACK_HOSTS),
+            (BindScope::AllInterfaces, WILDCARD_HOSTS),
+        ];
+        for protocol in [Protocol::Tcp, Protocol::Udp] {
+            for (scope, bound_hosts) in scopes {
+                for bound_ip in bound_hosts {
+                    let Some((socket, port)) = bound_socket(protocol, bound_ip) else {
+                        continue;
+                    };
+                    for family in families {
+                        let hosts = probe_hosts(None, Some(scope), family).unwrap();
+                        let usage = port_usage(port, &hosts, protocol);
+                        let case = format!("{protocol:?} {scope:?} {bound_ip} {family:?}");
+                        assert_eq!(usage.checked.len(), hosts.len(), "{case}");
+                        let bound_addr = SocketAddr::new(bound_ip, port).to_string();
+                        if family.includes(&bound_ip) {
+                            assert!(usage.in_use, "{case}");
+                            assert!(usage.occupied.contains(&bound_addr), "{case}");
+                            let flag = if bound_ip.is_ipv4() {
+                                usage.ipv4_in_use
+                            } else {
+                                usage.ipv6_in_use
+                            };
+                            assert!(flag, "{case}");
+                        }
+                        // 通配地址上的 IPv6 套接字默认同时接收 IPv4 连接（取决于系统的
+                        // IPV6_V6ONLY 设置），另一地址族的结果因平台而异，只对回环地址做精确断言
+                        if scope == BindScope::Loopback {
+                            let expected = family.includes(&bound_ip);
+                            assert_eq!(usage.in_use, expected, "{case}");
+                            assert_eq!(usage.ipv4_in_use, expected && bound_ip.is_ipv4(), "{case}");
+                            assert_eq!(usage.ipv6_in_use, expected && bound_ip.is_ipv6(), "{case}");
+                        }
+                    }
+                    drop(socket);
+                    for family in families {
+                        let hosts = probe_hosts(None, Some(scope), family).unwrap();
+                        let usage = port_usage(port, &hosts, protocol);
+                        let case = format!("{protocol:?} {scope:?} {family:?}");
+                        assert!(usage.occupied.is_empty(), "{case}");
+                        assert!(!usage.in_use, "{case}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lsof_args_select_only_listeners_on_the_port() {
+        let args = lsof_listen_args(5173);
+        assert!(args.iter().any(|arg| arg == "-sTCP:LISTEN"));
+        assert!(args.iter().any(|arg| arg == "-iTCP:5173"));
+        assert!(args.iter().any(|arg| arg == "-t"));
+    }
+
+    #[test]
+    fn lsof_pids_are_deduplicated_and_malformed_rows_dropped() {
+        let stdout =
+            "1234\n5678\n1234\n\n  91011  \nlsof: WARNING: can't stat() fuse\n-1\n99999999999\n";
+        assert_eq!(parse_lsof_pids(stdout), vec![1234, 5678, 91011]);
+        assert!(parse_lsof_pids("").is_empty());
+    }
+
+    #[test]
+    fn lsof_field_output_pairs_ports_with_pids() {
+        let stdout =
+            "p1234\nf5\nn127.0.0.1:5000\nf6\nn[::1]:5000\np5678\nf3\nn*:8080\nnlocalhost:http\n";
+        assert_eq!(
+            parse_lsof_listeners(stdout),
+            vec![(5000, 1234), (5000, 1234), (8080, 5678)]
+        );
+    }
+
+    #[test]
+    fn lsof_field_output_ignores_names_without_a_valid_pid() {
+        // `n` 行出现在任何 `p` 行之前，或所属 `p` 行无法解析时不归属任何进程
+        let stdout = "n*:3000\npabc\nn*:4000\np42\nn*:70000\nn*:4200\n";
+        assert_eq!(parse_lsof_listeners(stdout), vec![(4200, 42)]);
+    }
+
+    /// `netstat -ano` 的典型输出，表头为本地化文本
+    const NETSTAT_FIXTURE: &str = "
+活动连接
+
+  协议  本地地址          外部地址        状态           PID
+  TCP    0.0.0.0:80             0.0.0.0:0              LISTENING       1111
+  TCP    0.0.0.0:8080           0.0.0.0:0              LISTENING       2222
+  TCP    127.0.0.1:5000         0.0.0.0:0              LISTENING       3333
+  TCP    127.0.0.1:5000         127.0.0.1:61000        ESTABLISHED     3333
+  TCP    127.0.0.1:61000        127.0.0.1:5000         ESTABLISHED     4444
+  TCP    [::]:80                [::]:0                 LISTENING       1111
+  TCP    [::]:5000              [::]:0                 LISTENING       5555
+  TCP    [::1]:8081             [::]:0                 LISTENING       6666
+  TCP    [fe80::1%7]:139        [::]:0                 LISTENING       4
+  UDP    0.0.0.0:5353           *:*                                    7777
+  UDP    [::]:5353              *:*                                    7777
+";
+
+    #[test]
+    fn split_local_addr_handles_both_families() {
+        let cases = [
+            ("0.0.0.0:5000", Some(("0.0.0.0", 5000))),
+            ("127.0.0.1:80", Some(("127.0.0.1", 80))),
+            ("[::]:5000", Some(("::", 5000))),
+            ("[::1]:8080", Some(("::1", 8080))),
+            ("[fe80::1%7]:139", Some(("fe80::1%7", 139))),
+            ("*:*", None),
+            ("::1:80", None),
+            ("[::1:80", None),
+            ("127.0.0.1:70000", None),
+            ("127.0.0.1", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(split_local_addr(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn netstat_sockets_keep_listening_rows_in_order() {
+        assert_eq!(
+            parse_netstat_sockets(NETSTAT_FIXTURE, Protocol::Tcp),
+            vec![
+                (80, 1111),
+                (8080, 2222),
+                (5000, 3333),
+                (80, 1111),
+                (5000, 5555),
+                (8081, 6666),
+                (139, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn netstat_listeners_match_ports_numerically() {
+        let cases: [(u16, &[u32]); 6] = [
+            // `:80` 不能匹配 `:8080` / `:8081`
+            (80, &[1111]),
+            (8080, &[2222]),
+            (8081, &[6666]),
+            // IPv4 回环与 IPv6 通配上的监听都算；ESTABLISHED 行不算
+            (5000, &[3333, 5555]),
+            (8, &[]),
+            (61000, &[]),
+        ];
+        for (port, expected) in cases {
+            assert_eq!(
+                parse_netstat_listeners(NETSTAT_FIXTURE, port, Protocol::Tcp),
+                expected,
+                "port {port}"
+            );
+        }
+    }
+
+    #[test]
+    fn netstat_detects_ipv6_wildcard_listener() {
+        let stdout = "  TCP    [::]:5000              [::]:0                 LISTENING       42\n";
+        assert_eq!(
+            parse_netstat_listeners(stdout, 5000, Protocol::Tcp),
+            vec![42]
+        );
+        let stdout = "  TCP    [::]:5000              [::]:0                 TIME_WAIT       0\n";
+        assert!(parse_netstat_listeners(stdout, 5000, Protocol::Tcp).is_empty());
+    }
+
+    #[test]
+    fn netstat_udp_rows_take_pid_from_fourth_column() {
+        assert_eq!(
+            parse_netstat_sockets(NETSTAT_FIXTURE, Protocol::Udp),
+            vec![(5353, 7777), (5353, 7777)]
+        );
+        assert_eq!(
+            parse_netstat_listeners(NETSTAT_FIXTURE, 5353, Protocol::Udp),
+            vec![7777]
+        );
+        // UDP 不会匹配同端口的 TCP 行
+        assert!(parse_netstat_listeners(NETSTAT_FIXTURE, 80, Protocol::Udp).is_empty());
+    }
+
+    fn sample_lines(error: PortError) -> Vec<String> {
+        match error {
+            PortError::ParseMismatch { sample_lines } => sample_lines,
+            other => panic!("不是 ParseMismatch: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_mismatch_samples_garbled_output() {
+        let cases: &[(&str, &str, u16, &[&str])] = &[
+            (
+                "提到端口的行优先",
+                "Aktive Verbindungen\n\n  Proto  Lokale Adresse\n  TCP 0.0.0.0:8080 ABHÖREN 42\n",
+                8080,
+                &["TCP 0.0.0.0:8080 ABHÖREN 42"],
+            ),
+            (
+                "端口号按完整数字匹配",
+                "TCP 0.0.0.0:8080 x\nTCP [::]:80 y\nTCP 0.0.0.0:80\n",
+                80,
+                &["TCP [::]:80 y", "TCP 0.0.0.0:80"],
+            ),
+            (
+                "没有提到端口时取开头的非空行",
+                "\n?? garbled ??\n\n\u{fffd}\u{fffd}\nline 3\n",
+                5000,
+                &["?? garbled ??", "\u{fffd}\u{fffd}", "line 3"],
+            ),
+            (
+                "最多附带 5 行",
+                "a:1\nb:1\nc:1\nd:1\ne:1\nf:1\n",
+                1,
+                &["a:1", "b:1", "c:1", "d:1", "e:1"],
+            ),
+            ("没有输出", "  \n\n", 80, &[]),
+        ];
+        for (name, stdout, port, expected) in cases {
+            assert_eq!(
+                sample_lines(parse_mismatch(stdout, *port)),
+                *expected,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn garbled_netstat_output_for_a_bound_port_is_a_parse_mismatch() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // 德语系统的状态列为 ABHÖREN，所有行都不会被识别为监听
+        let garbled = format!(
+            "Aktive Verbindungen\n\n  Proto  Lokale Adresse  Remoteadresse  Status  PID\n  \
+             TCP    127.0.0.1:{port}  0.0.0.0:0  ABHÖREN  4242\n  \
+             TCP    0.0.0.0:135  0.0.0.0:0  ABHÖREN  900\n"
+        );
+        let probe = || port_usage(port, &DEFAULT_PROBE_HOSTS, Protocol::Tcp).in_use;
+
+        let error = netstat_pids_or_mismatch(&garbled, port, Protocol::Tcp, probe).unwrap_err();
+        assert_eq!(
+            sample_lines(error),
+            vec![format!("TCP    127.0.0.1:{port}  0.0.0.0:0  ABHÖREN  4242")]
+        );
+
+        let parsed = format!("  TCP    127.0.0.1:{port}  0.0.0.0:0  LISTENING  4242\n");
+        assert_eq!(
+            netstat_pids_or_mismatch(&parsed, port, Protocol::Tcp, || unreachable!()).unwrap(),
+            vec![4242]
+        );
+
+        drop(listener);
+        // 端口确实空闲时没有解析出 PID 是正常结果
+        assert!(
+            netstat_pids_or_mismatch(&garbled, port, Protocol::Tcp, probe)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn netstat_short_or_garbled_rows_do_not_panic() {
+        let stdout = "UDP\nUDP 0.0.0.0:53\nUDP 0.0.0.0:53 *:*\nTCP 0.0.0.0:53 0.0.0.0:0 LISTENING\nUDP 0.0.0.0:53 *:* abc\n";
+        assert!(parse_netstat_sockets(stdout, Protocol::Udp).is_empty());
+        assert!(parse_netstat_sockets(stdout, Protocol::Tcp).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn proc_net_udp_rows_are_not_filtered_by_state() {
+        let content = "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  123: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000  1000        0 23456 2 0000000000000000 0
+  124: 0100007F:1388 0100007F:A000 01 00000000:00000000 00:00000000 00000000  1000        0 23457 2 0000000000000000 0
+";
+        assert_eq!(
+            parse_proc_net_sockets(content, Protocol::Udp),
+            vec![(5353, 23456), (5000, 23457)]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn proc_net_tcp_keeps_only_listen_rows() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:1388 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 11111 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0050 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 22222 1 0000000000000000 100 0 0 10 0
+   2: 0100007F:1388 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 33333 1 0000000000000000 20 4 30 10 -1
+   3: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 0 1 0000000000000000 100 0 0 10 0
+";
+        // `1388` = 5000、`0050` = 80；ESTABLISHED（`01`）行与 inode 为 0 的行被丢弃
+        assert_eq!(
+            parse_proc_net_sockets(tcp, Protocol::Tcp),
+            vec![(5000, 11111), (80, 22222)]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn proc_net_tcp6_decodes_ports_after_long_addresses() {
+        let tcp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:1F90 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 44444 1 0000000000000000 100 0 0 10 0
+   1: 00000000000000000000000001000000:FFFF 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 55555 1 0000000000000000 100 0 0 10 0
+   2: 00000000000000000000000001000000:1F90 00000000000000000000000001000000:C350 06 00000000:00000000 03:00000A8C 00000000     0        0 66666 3 0000000000000000
+   3: 00000000000000000000000001000000:ZZZZ 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 77777 1 0000000000000000 100 0 0 10 0
+";
+        // 非 `0A`（此处为 TIME_WAIT `06`）与端口不是十六进制的行被丢弃
+        assert_eq!(
+            parse_proc_net_sockets(tcp6, Protocol::Tcp),
+            vec![(8080, 44444), (65535, 55555)]
+        );
+    }
+
+    fn exited(code: i32, stdout: &str) -> Output {
+        #[cfg(unix)]
+        let status = {
+            use std::os::unix::process::ExitStatusExt;
+            std::process::ExitStatus::from_raw(code << 8)
+        };
+        #[cfg(windows)]
+        let status = {
+            use std::os::windows::process::ExitStatusExt;
+            std::process::ExitStatus::from_raw(code as u32)
+        };
+        Output {
+            status,
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn retry_recovers_after_transient_failures() {
+        let mut calls = 0;
+        let output = retry_transient("netstat", NETSTAT_ATTEMPTS, Duration::ZERO, || {
+            calls += 1;
+            Ok(if calls <= 2 {
+                exited(1, "")
+            } else {
+                exited(0, "TCP 0.0.0.0:80")
+            })
+        })
+        .unwrap();
+        assert_eq!(calls, 3);
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn retry_stops_on_output_errors_or_last_attempt() {
+        let mut calls = 0;
+        let output = retry_transient("netstat", NETSTAT_ATTEMPTS, Duration::ZERO, || {
+            calls += 1;
+            Ok(exited(1, ""))
+        })
+        .unwrap();
+        assert_eq!(calls, NETSTAT_ATTEMPTS);
+        assert_eq!(output.status.code(), Some(1));
+
+        // 失败但有输出时交给调用方解析
+        calls = 0;
+        retry_transient("netstat", NETSTAT_ATTEMPTS, Duration::ZERO, || {
+            calls += 1;
+            Ok(exited(1, "partial"))
+        })
+        .unwrap();
+        assert_eq!(calls, 1);
+
+        calls = 0;
+        let error = retry_transient("netstat", NETSTAT_ATTEMPTS, Duration::ZERO, || {
+            calls += 1;
+            Err(PortError::ToolNotInstalled {
+                tool: "netstat".into(),
+            })
+        });
+        assert!(matches!(error, Err(PortError::ToolNotInstalled { .. })));
+        assert_eq!(calls, 1);
+    }
+
+    fn connect(host: &str, port: u16) -> bool {
+        tauri::async_runtime::block_on(can_connect_to(host, port, Duration::from_secs(2)))
+    }
+
+    #[test]
+    fn can_connect_to_live_listener_but_not_closed_port() {
+        let (listener, port) = bound_tcp(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        assert!(connect("127.0.0.1", port));
+        drop(listener);
+        assert!(!connect("127.0.0.1", port));
+
+        if let Some((_listener, port)) = bound_tcp(IpAddr::V6(Ipv6Addr::LOCALHOST)) {
+            assert!(connect("[::1]", port));
+            assert!(connect("::1", port));
+        }
+    }
+
+    #[test]
+    fn validate_port_rejects_only_zero() {
+        assert!(matches!(
+            validate_port(0),
+            Err(PortError::InvalidPort { port: 0 })
+        ));
+        for port in [1, 8080, u16::MAX] {
+            assert!(validate_port(port).is_ok());
+        }
+    }
+
+    #[test]
+    fn localhost_resolves_to_loopback_addresses() {
+        let ips = resolve_host("localhost").unwrap();
+        assert!(!ips.is_empty());
+        assert!(ips.iter().all(IpAddr::is_loopback), "{ips:?}");
+        assert_eq!(
+            resolve_host(" [::1] ").unwrap(),
+            vec![IpAddr::V6(Ipv6Addr::LOCALHOST)]
+        );
+
+        let (_listener, port) = bound_tcp(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        assert!(connect("localhost", port));
+    }
+
+    #[test]
+    fn invalid_hosts_are_rejected() {
+        // `.invalid` 保证不会被解析（RFC 2606）
+        for host in ["", "  ", "[]", "no-such-host.invalid", "bad host"] {
+            assert!(
+                matches!(resolve_host(host), Err(PortError::InvalidHost { .. })),
+                "{host:?}"
+            );
+        }
+        let (_listener, port) = bound_tcp(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+        assert!(!connect("no-such-host.invalid", port));
+    }
+
+    #[test]
+    fn missing_tool_is_reported_as_not_installed() {
+        let tool = "/nonexistent/openreview-missing-tool";
+        match run_tool(tool, &["--version"]) {
+            Err(PortError::ToolNotInstalled { tool: reported }) => assert_eq!(reported, tool),
+            other => panic!("应报告命令未安装: {other:?}"),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn proc_lookup_finds_our_own_listeners() {
+        let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+        let tcp_port = tcp.local_addr().unwrap().port();
+        let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let udp_port = udp.local_addr().unwrap().port();
+        let own = vec![std::process::id()];
+
+        assert_eq!(
+            linux_listeners_from_proc(tcp_port, Protocol::Tcp).unwrap(),
+            own
+        );
+        assert_eq!(
+            linux_listeners_from_proc(udp_port, Protocol::Udp).unwrap(),
+            own
+        );
+        assert!(linux_tcp_listeners_from_proc()
+            .unwrap()
+            .contains(&(tcp_port, std::process::id())));
+        drop((tcp, udp));
+        assert!(linux_listeners_from_proc(tcp_port, Protocol::Tcp)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,964 @@
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sysinfo::{
+    Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind, Users,
+    MINIMUM_CPU_UPDATE_INTERVAL,
+};
+use tracing::{debug, info};
+
+use crate::ports::{
+    can_bind, command_failed, pids_listening_on, probe_concurrently, run_tool, tcp_listeners,
+    PortError, Protocol,
+};
+
+const KILL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// 系统关键进程：Windows 的 System Idle (0) / System (4)；Unix 的 init (1)，
+/// 0 在 `kill` 中表示整个进程组
+#[cfg(target_os = "windows")]
+const PROTECTED_PIDS: [u32; 2] = [0, 4];
+#[cfg(not(target_os = "windows"))]
+const PROTECTED_PIDS: [u32; 2] = [0, 1];
+/// taskkill 的退出码：拒绝访问（目标属于其他用户或提权运行的服务）与进程不存在
+const TASKKILL_ACCESS_DENIED: i32 = 5;
+const TASKKILL_NOT_FOUND: i32 = 128;
+/// 单次扫描允许的最大端口数，避免误触发全范围扫描
+const MAX_SCAN_PORTS: u16 = 1024;
+
+/// 进程信息，`start_time` 为 Unix 时间戳（秒）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub command_line: String,
+    pub user: Option<String>,
+    pub start_time: u64,
+    /// 可执行文件名（不含路径），无权限读取时为 `None`
+    pub executable: Option<String>,
+}
+
+/// 补充进程详情，查询期间已退出的进程会被忽略
+pub(crate) fn describe_processes(pids: &[u32]) -> Vec<ProcessInfo> {
+    if pids.is_empty() {
+        return Vec::new();
+    }
+
+    let targets: Vec<Pid> = pids.iter().map(|pid| Pid::from_u32(*pid)).collect();
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&targets),
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cmd(UpdateKind::Always)
+            .with_exe(UpdateKind::Always)
+            .with_user(UpdateKind::Always),
+    );
+    let users = Users::new_with_refreshed_list();
+
+    pids.iter()
+        .filter_map(|pid| {
+            let process = system.process(Pid::from_u32(*pid))?;
+            Some(ProcessInfo {
+                pid: *pid,
+                name: process.name().to_string_lossy().into_owned(),
+                command_line: process
+                    .cmd()
+                    .iter()
+                    .map(|arg| arg.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                user: process
+                    .user_id()
+                    .and_then(|uid| users.get_user_by_id(uid))
+                    .map(|user| user.name().to_string()),
+                start_time: process.start_time(),
+                executable: process
+                    .exe()
+                    .and_then(|exe| exe.file_name())
+                    .map(|name| name.to_string_lossy().into_owned()),
+            })
+        })
+        .collect()
+}
+
+/// 进程资源占用；`cpu_percent` 以单核为 100%，多核进程可能超过 100
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcStats {
+    pub pid: u32,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// 采样进程的 CPU 与内存占用。CPU 占用需要两次采样的差值，因此会阻塞约 200ms；
+/// 进程不存在时返回 `None`
+pub(crate) fn process_stats(pid: u32) -> Option<ProcStats> {
+    let target = [Pid::from_u32(pid)];
+    let refresh = ProcessRefreshKind::nothing().with_cpu().with_memory();
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&target), true, refresh);
+    thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&target), true, refresh);
+
+    let process = system.process(target[0])?;
+    Some(ProcStats {
+        pid,
+        cpu_percent: process.cpu_usage(),
+        memory_bytes: process.memory(),
+    })
+}
+
+/// 进程的启动时间（Unix 秒）与可执行文件完整路径，用于确认 PID 没有被其他程序复用；
+/// 进程不存在时返回 `None`
+pub(crate) fn process_identity(pid: u32) -> Option<(u64, Option<String>)> {
+    let target = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[target]),
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::Always),
+    );
+    let process = system.process(target)?;
+    let executable = process.exe().map(|exe| exe.to_string_lossy().into_owned());
+    Some((process.start_time(), executable))
+}
+
+/// 当前进程及其父进程（`tauri dev` 下通常是 cargo），结束它们会直接带走应用窗口；
+/// `backends` 为应用启动的后端，应通过 `stop_backend` 停止
+pub(crate) fn own_pids(backends: &[u32]) -> Vec<u32> {
+    let own = Pid::from_u32(std::process::id());
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[own]), true);
+
+    let mut pids = vec![own.as_u32()];
+    if let Some(parent) = system.process(own).and_then(|process| process.parent()) {
+        pids.push(parent.as_u32());
+    }
+    pids.extend_from_slice(backends);
+    pids
+}
+
+fn is_protected_pid(pid: u32) -> bool {
+    PROTECTED_PIDS.contains(&pid)
+}
+
+/// 按 PID 结束进程前的检查：拒绝系统关键进程、应用自身以及 `backends` 中应用启动的后端
+pub(crate) fn ensure_killable(pid: u32, backends: &[u32]) -> Result<(), PortError> {
+    if is_protected_pid(pid) {
+        return Err(PortError::ProtectedPid { pid });
+    }
+    if own_pids(backends).contains(&pid) {
+        return Err(PortError::WouldKillSelf);
+    }
+    Ok(())
+}
+
+/// 返回 `roots` 及其全部后代进程，子进程排在父进程之前，便于按此顺序逐个结束
+pub(crate) fn tree_kill_order(roots: &[u32]) -> Vec<u32> {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (pid, process) in system.processes() {
+        // Linux 下线程也会出现在进程表中，结束线程等同于结束所属进程，这里忽略
+        if process.thread_kind().is_some() {
+            continue;
+        }
+        if let Some(parent) = process.parent() {
+            children
+                .entry(parent.as_u32())
+                .or_default()
+                .push(pid.as_u32());
+        }
+    }
+
+    let mut order: Vec<u32> = Vec::new();
+    for root in roots {
+        if !order.contains(root) {
+            order.push(*root);
+        }
+    }
+    let mut index = 0;
+    while index < order.len() {
+        for child in children.get(&order[index]).into_iter().flatten() {
+            if !order.contains(child) {
+                order.push(*child);
+            }
+        }
+        index += 1;
+    }
+    // 广度优先遍历中父进程总在子进程之前，反转后即为子进程优先
+    order.reverse();
+    order
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn terminate(pid: u32, force: bool) -> Result<(), PortError> {
+    debug!(pid, force, "结束进程");
+    let pid_arg = pid.to_string();
+    let mut args = vec!["/PID", pid_arg.as_str()];
+    if force {
+        args.push("/F");
+    }
+    let output = run_tool("taskkill", &args)?;
+    taskkill_result(pid, output.status.code())
+}
+
+/// 将 taskkill 的退出码映射为结束进程的结果，`None` 表示进程被信号终止
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn taskkill_result(pid: u32, code: Option<i32>) -> Result<(), PortError> {
+    match code {
+        Some(0) => Ok(()),
+        Some(TASKKILL_ACCESS_DENIED) => Err(PortError::PermissionDenied { pid }),
+        Some(TASKKILL_NOT_FOUND) => Err(PortError::NoSuchProcess { pid }),
+        code => Err(PortError::CommandFailed {
+            tool: "taskkill".to_string(),
+            code: code.unwrap_or(-1),
+        }),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn terminate(pid: u32, force: bool) -> Result<(), PortError> {
+    debug!(pid, force, "结束进程");
+    let signal = if force { "-KILL" } else { "-TERM" };
+    let output = run_tool("kill", &[signal, &pid.to_string()])?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+        if stderr.contains("not permitted") {
+            return Err(PortError::PermissionDenied { pid });
+        }
+        if stderr.contains("no such process") {
+            return Err(PortError::NoSuchProcess { pid });
+        }
+        return Err(command_failed("kill", output.status));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    run_tool(
+        "tasklist",
+        &["/FI", &format!("PID eq {pid}"), "/NH", "/FO", "CSV"],
+    )
+    .map(|output| String::from_utf8_lossy(&output.stdout).contains(&format!("\"{pid}\"")))
+    .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    // kill -0 只检查进程是否存在；无权限发送信号时进程同样存活
+    run_tool("kill", &["-0", &pid.to_string()])
+        .map(|output| {
+            output.status.success()
+                || String::from_utf8_lossy(&output.stderr)
+                    .to_lowercase()
+                    .contains("not permitted")
+        })
+        .unwrap_or(false)
+}
+
+pub(crate) fn wait_for_exit(pids: &[u32], timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    while pids.iter().any(|pid| process_alive(*pid)) && Instant::now() < deadline {
+        thread::sleep(KILL_POLL_INTERVAL);
+    }
+}
+
+/// 按子进程优先的顺序结束 `root` 及其后代，返回实际发出信号的 PID。
+/// 非强制模式下先发送 SIGTERM，超过 `grace` 仍存活的再强制结束
+pub(crate) fn kill_tree(root: u32, force: bool, grace: Duration) -> Result<Vec<u32>, PortError> {
+    let mut signalled = Vec::new();
+    for pid in tree_kill_order(&[root]) {
+        match terminate(pid, force) {
+            Ok(()) => signalled.push(pid),
+            Err(PortError::NoSuchProcess { .. }) => {}
+            Err(error) => return Err(error),
+        }
+    }
+    if signalled.is_empty() {
+        return Err(PortError::NoSuchProcess { pid: root });
+    }
+
+    if !force {
+        wait_for_exit(&signalled, grace);
+        for pid in &signalled {
+            if process_alive(*pid) {
+                match terminate(*pid, true) {
+                    Ok(()) | Err(PortError::NoSuchProcess { .. }) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+        }
+    }
+    Ok(signalled)
+}
+
+/// 结束进程所采用的方式，`Forced` 意味着进程可能没有机会完成清理
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KillStrategy {
+    Graceful,
+    Forced,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KilledProcess {
+    pub pid: u32,
+    pub name: String,
+    pub strategy: KillStrategy,
+}
+
+/// 进程未被结束的原因
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum SkipReason {
+    AlreadyExited,
+    PermissionDenied,
+    /// 当前应用自身、其父进程或应用启动的后端
+    OwnProcess,
+    /// 可执行文件名不在 `expected_names` 中，或无法读取
+    NameMismatch,
+    /// 系统关键进程
+    Protected,
+    Failed {
+        message: String,
+    },
+}
+
+impl From<PortError> for SkipReason {
+    fn from(error: PortError) -> Self {
+        match error {
+            PortError::NoSuchProcess { .. } => Self::AlreadyExited,
+            PortError::PermissionDenied { .. } => Self::PermissionDenied,
+            other => Self::Failed {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedProcess {
+    pub pid: u32,
+    pub name: String,
+    pub reason: SkipReason,
+}
+
+/// `force_kill_process_on_port` 的执行结果，部分进程失败时仍会返回已结束的进程
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillSummary {
+    pub killed: Vec<KilledProcess>,
+    pub skipped: Vec<SkippedProcess>,
+    /// 仅 `dry_run` 时填充：将会被结束的进程，实际未发送任何信号
+    pub targets: Vec<ProcessInfo>,
+}
+
+impl KillSummary {
+    fn kill(&mut self, process: ProcessInfo, strategy: KillStrategy) {
+        self.killed.push(KilledProcess {
+            pid: process.pid,
+            name: process.name,
+            strategy,
+        });
+    }
+
+    fn skip(&mut self, process: ProcessInfo, reason: SkipReason) {
+        self.skipped.push(SkippedProcess {
+            pid: process.pid,
+            name: process.name,
+            reason,
+        });
+    }
+}
+
+/// 结束单个已确认进程的结果
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KillOutcome {
+    /// 确认前进程已经退出
+    AlreadyExited,
+    Graceful,
+    Forced,
+}
+
+/// 结束界面上已确认的单个进程。`expected_name` 用于防止 PID 在确认期间被复用，
+/// 名称不符时拒绝结束；`force` 为 `true` 时跳过正常退出阶段直接强制结束
+pub(crate) fn kill_confirmed(
+    pid: u32,
+    force: bool,
+    expected_name: Option<&str>,
+    grace: Duration,
+) -> Result<KillOutcome, PortError> {
+    let Some(process) = describe_processes(&[pid]).pop() else {
+        return Ok(KillOutcome::AlreadyExited);
+    };
+    if let Some(expected) = expected_name {
+        let matches = process
+            .executable
+            .as_deref()
+            .is_some_and(|executable| executable_matches(executable, expected.trim()));
+        if !matches {
+            return Err(PortError::ProcessChanged {
+                pid,
+                actual: process.executable,
+            });
+        }
+    }
+
+    if !force {
+        match terminate(pid, false) {
+            Err(PortError::NoSuchProcess { .. }) => return Ok(KillOutcome::AlreadyExited),
+            result => result?,
+        }
+        wait_for_exit(&[pid], grace);
+        if !process_alive(pid) {
+            return Ok(KillOutcome::Graceful);
+        }
+    }
+
+    match terminate(pid, true) {
+        Ok(()) => Ok(KillOutcome::Forced),
+        // 强制结束前进程已自行退出
+        Err(PortError::NoSuchProcess { .. }) if !force => Ok(KillOutcome::Graceful),
+        Err(PortError::NoSuchProcess { .. }) => Ok(KillOutcome::AlreadyExited),
+        Err(error) => Err(error),
+    }
+}
+
+/// 端口清理选项
+#[derive(Debug, Clone)]
+pub(crate) struct KillOptions {
+    pub grace: Duration,
+    /// 允许结束应用自身、其父进程及 `backends`
+    pub allow_self: bool,
+    /// 应用启动的后端，与应用自身一样默认跳过
+    pub backends: Vec<u32>,
+    pub include_children: bool,
+    /// 仅结束可执行文件名在列表中的进程，`None` 表示不限制
+    pub expected_names: Option<Vec<String>>,
+    pub protocol: Protocol,
+    /// 只解析目标进程，不结束任何进程
+    pub dry_run: bool,
+}
+
+#[cfg(target_os = "windows")]
+fn executable_matches(executable: &str, expected: &str) -> bool {
+    // Windows 文件名不区分大小写，且允许省略 `.exe` 后缀
+    let executable = executable.to_lowercase();
+    let expected = expected.to_lowercase();
+    executable == expected || executable.strip_suffix(".exe") == Some(expected.as_str())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn executable_matches(executable: &str, expected: &str) -> bool {
+    executable == expected
+}
+
+impl KillOptions {
+    fn allows(&self, process: &ProcessInfo) -> bool {
+        let Some(expected) = &self.expected_names else {
+            return true;
+        };
+        process.executable.as_deref().is_some_and(|executable| {
+            expected
+                .iter()
+                .any(|name| executable_matches(executable, name.trim()))
+        })
+    }
+}
+
+/// 先请求进程正常退出（SIGTERM / 不带 `/F` 的 taskkill），
+/// 等待 `grace` 后仍存活的进程再强制结束；单个进程失败不会中断其余进程的处理。
+/// 默认跳过应用自身、其父进程及应用启动的后端；
+/// `include_children` 会连同监听进程的子进程一起结束（子进程优先）；
+/// 指定 `expected_names` 时名称不符的进程记为 `NameMismatch`，不会被结束；
+/// `dry_run` 时只把将被结束的进程放入 `targets`
+pub(crate) fn kill_port_listeners(
+    port: u16,
+    options: &KillOptions,
+) -> Result<KillSummary, PortError> {
+    let own_pids = if options.allow_self {
+        Vec::new()
+    } else {
+        own_pids(&options.backends)
+    };
+    let mut pids = pids_listening_on(port, options.protocol)?;
+    if options.include_children {
+        pids = tree_kill_order(&pids);
+    }
+    // 在发出任何信号前剔除系统关键进程，仅当没有其他候选时才报错
+    let (protected, pids): (Vec<u32>, Vec<u32>) =
+        pids.into_iter().partition(|pid| is_protected_pid(*pid));
+    if let (Some(pid), true) = (protected.first(), pids.is_empty()) {
+        return Err(PortError::ProtectedPid { pid: *pid });
+    }
+    let (own, targets): (Vec<_>, Vec<_>) = describe_processes(&pids)
+        .into_iter()
+        .partition(|process| own_pids.contains(&process.pid));
+    if targets.is_empty() && !own.is_empty() {
+        return Err(PortError::WouldKillSelf);
+    }
+
+    let mut summary = KillSummary::default();
+    for process in describe_processes(&protected) {
+        summary.skip(process, SkipReason::Protected);
+    }
+    for process in own {
+        summary.skip(process, SkipReason::OwnProcess);
+    }
+
+    let mut pending = Vec::new();
+    for process in targets {
+        if !options.allows(&process) {
+            summary.skip(process, SkipReason::NameMismatch);
+            continue;
+        }
+        if options.dry_run {
+            summary.targets.push(process);
+            continue;
+        }
+        match terminate(process.pid, false) {
+            Ok(()) => pending.push(process),
+            Err(error) => summary.skip(process, error.into()),
+        }
+    }
+
+    let pids: Vec<u32> = pending.iter().map(|process| process.pid).collect();
+    wait_for_exit(&pids, options.grace);
+
+    for process in pending {
+        if !process_alive(process.pid) {
+            summary.kill(process, KillStrategy::Graceful);
+            continue;
+        }
+        match terminate(process.pid, true) {
+            Ok(()) => summary.kill(process, KillStrategy::Forced),
+            // 强制结束前进程已自行退出
+            Err(PortError::NoSuchProcess { .. }) => summary.kill(process, KillStrategy::Graceful),
+            Err(error) => summary.skip(process, error.into()),
+        }
+    }
+
+    info!(
+        port,
+        killed = summary.killed.len(),
+        skipped = summary.skipped.len(),
+        "端口清理完成"
+    );
+    Ok(summary)
+}
+
+/// `listening_ports` 的一项；无权读取进程详情时 `process_name` 为空
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortEntry {
+    pub port: u16,
+    pub pid: u32,
+    pub process_name: String,
+    pub protocol: Protocol,
+}
+
+/// 本机所有处于 LISTEN 状态的 TCP 端口及其进程，按端口号升序；
+/// 同一进程同时监听 IPv4 与 IPv6 时只列一项，查询期间已退出的进程被忽略
+pub(crate) fn listening_ports() -> Result<Vec<PortEntry>, PortError> {
+    let listeners = tcp_listeners()?;
+    let mut pids: Vec<u32> = listeners.iter().map(|(_, pid)| *pid).collect();
+    pids.sort_unstable();
+    pids.dedup();
+    let names: HashMap<u32, String> = describe_processes(&pids)
+        .into_iter()
+        .map(|process| (process.pid, process.name))
+        .collect();
+    Ok(listeners
+        .into_iter()
+        .map(|(port, pid)| PortEntry {
+            port,
+            pid,
+            process_name: names.get(&pid).cloned().unwrap_or_default(),
+            protocol: Protocol::Tcp,
+        })
+        .collect())
+}
+
+/// 端口扫描结果，无法识别占用进程时 `processes` 为空
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortStatus {
+    pub port: u16,
+    pub in_use: bool,
+    pub processes: Vec<ProcessInfo>,
+}
+
+/// 并发探测闭区间 `[start, end]` 内的端口，结果按端口号升序排列
+pub(crate) fn scan_port_range(start: u16, end: u16) -> Result<Vec<PortStatus>, PortError> {
+    if start > end {
+        return Err(PortError::InvalidRange { start, end });
+    }
+    if u32::from(end - start) >= u32::from(MAX_SCAN_PORTS) {
+        return Err(PortError::RangeTooLarge {
+            start,
+            end,
+            max: MAX_SCAN_PORTS,
+        });
+    }
+
+    let ports: Vec<u16> = (start..=end).collect();
+    let probed = probe_concurrently(&ports, |port| (port, !can_bind(port)));
+
+    // 逐个端口查找占用进程时每次都要重新读取 socket 表（Linux 上还要遍历所有进程的 fd），
+    // 这里只读取一次全部监听；查找失败不影响占用结果
+    let mut owners: HashMap<u16, Vec<u32>> = HashMap::new();
+    if probed.iter().any(|(_, in_use)| *in_use) {
+        for (port, pid) in tcp_listeners().unwrap_or_default() {
+            if (start..=end).contains(&port) {
+                owners.entry(port).or_default().push(pid);
+            }
+        }
+    }
+
+    // 同一进程可能监听多个端口，统一查询一次进程详情
+    let mut pids: Vec<u32> = owners.values().flatten().copied().collect();
+    pids.sort_unstable();
+    pids.dedup();
+    let processes: HashMap<u32, ProcessInfo> = describe_processes(&pids)
+        .into_iter()
+        .map(|process| (process.pid, process))
+        .collect();
+
+    Ok(probed
+        .into_iter()
+        .map(|(port, in_use)| PortStatus {
+            port,
+            in_use,
+            processes: owners
+                .get(&port)
+                .filter(|_| in_use)
+                .into_iter()
+                .flatten()
+                .filter_map(|pid| processes.get(pid).cloned())
+                .collect(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: format!("server-{pid}"),
+            command_line: String::new(),
+            user: None,
+            start_time: 0,
+            executable: None,
+        }
+    }
+
+    #[test]
+    fn kill_errors_map_to_skip_reasons() {
+        assert!(matches!(
+            SkipReason::from(PortError::NoSuchProcess { pid: 1 }),
+            SkipReason::AlreadyExited
+        ));
+        assert!(matches!(
+            SkipReason::from(PortError::PermissionDenied { pid: 1 }),
+            SkipReason::PermissionDenied
+        ));
+        let failed = SkipReason::from(PortError::CommandFailed {
+            tool: "kill".into(),
+            code: 2,
+        });
+        assert!(matches!(failed, SkipReason::Failed { message } if message.contains("kill")));
+    }
+
+    #[test]
+    fn summary_reports_each_process_once() {
+        let mut summary = KillSummary::default();
+        summary.kill(process(10), KillStrategy::Graceful);
+        summary.kill(process(11), KillStrategy::Forced);
+        summary.skip(process(12), PortError::PermissionDenied { pid: 12 }.into());
+        summary.skip(process(13), SkipReason::NameMismatch);
+
+        let killed: Vec<(u32, &str)> = summary
+            .killed
+            .iter()
+            .map(|killed| (killed.pid, killed.name.as_str()))
+            .collect();
+        assert_eq!(killed, vec![(10, "server-10"), (11, "server-11")]);
+        assert!(matches!(summary.killed[1].strategy, KillStrategy::Forced));
+        let skipped: Vec<u32> = summary.skipped.iter().map(|skipped| skipped.pid).collect();
+        assert_eq!(skipped, vec![12, 13]);
+        assert!(matches!(
+            summary.skipped[0].reason,
+            SkipReason::PermissionDenied
+        ));
+        assert!(summary.targets.is_empty());
+    }
+
+    #[test]
+    fn scan_reports_own_listener() {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let statuses = scan_port_range(port, port).unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert!(statuses[0].in_use);
+        assert!(statuses[0]
+            .processes
+            .iter()
+            .all(|process| process.pid == std::process::id()));
+        drop(listener);
+        assert!(!scan_port_range(port, port).unwrap()[0].in_use);
+    }
+
+    #[test]
+    fn scan_rejects_oversized_ranges() {
+        assert!(matches!(
+            scan_port_range(2000, 1000),
+            Err(PortError::InvalidRange { .. })
+        ));
+        assert!(matches!(
+            scan_port_range(1, u16::MAX),
+            Err(PortError::RangeTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn protected_and_own_pids_are_refused() {
+        for pid in PROTECTED_PIDS {
+            assert!(matches!(
+                ensure_killable(pid, &[]),
+                Err(PortError::ProtectedPid { pid: refused }) if refused == pid
+            ));
+        }
+        assert!(matches!(
+            ensure_killable(std::process::id(), &[]),
+            Err(PortError::WouldKillSelf)
+        ));
+    }
+
+    #[test]
+    fn backend_children_count_as_own_processes() {
+        let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+
+        assert!(!own_pids(&[]).contains(&pid));
+        assert!(own_pids(&[pid]).contains(&pid));
+        assert!(own_pids(&[pid]).contains(&std::process::id()));
+        assert!(ensure_killable(pid, &[]).is_ok());
+        assert!(matches!(
+            ensure_killable(pid, &[pid]),
+            Err(PortError::WouldKillSelf)
+        ));
+    }
+
+    /// 启动 `sleep 30` 并返回其 PID 与退出状态的接收端
+    #[cfg(unix)]
+    fn spawn_sleep() -> (
+        u32,
+        std::sync::mpsc::Receiver<std::io::Result<std::process::ExitStatus>>,
+    ) {
+        let child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        // 由单独的线程回收子进程，否则僵尸进程会一直被视为存活
+        let (exited, reaped) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            let mut child = child;
+            let _ = exited.send(child.wait());
+        });
+        (pid, reaped)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kill_pid_terminates_confirmed_child() {
+        let (pid, reaped) = spawn_sleep();
+
+        ensure_killable(pid, &[]).unwrap();
+        // 与界面一样传入确认时展示的可执行文件名；部分系统上 sleep 是 coreutils 的链接
+        let shown = describe_processes(&[pid])
+            .pop()
+            .and_then(|process| process.executable);
+        let outcome = kill_confirmed(pid, false, shown.as_deref(), Duration::from_secs(5)).unwrap();
+        assert!(matches!(outcome, KillOutcome::Graceful));
+        let status = reaped
+            .recv_timeout(Duration::from_secs(5))
+            .expect("子进程未退出")
+            .unwrap();
+        assert!(!status.success());
+        assert!(!process_alive(pid));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn forced_kill_pid_skips_the_graceful_stage() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let (pid, reaped) = spawn_sleep();
+        let outcome = kill_confirmed(pid, true, None, Duration::from_secs(5)).unwrap();
+        assert!(matches!(outcome, KillOutcome::Forced));
+        let status = reaped
+            .recv_timeout(Duration::from_secs(5))
+            .expect("子进程未退出")
+            .unwrap();
+        assert_eq!(status.signal(), Some(9));
+        assert!(!process_alive(pid));
+        assert!(matches!(
+            kill_confirmed(pid, true, None, Duration::from_secs(5)).unwrap(),
+            KillOutcome::AlreadyExited
+        ));
+    }
+
+    const LISTENER_ENV: &str = "OPENREVIEW_TEST_LISTENER";
+
+    /// 由 `spawn_listener` 以子进程方式运行：监听临时端口，输出端口号后等待被结束
+    #[test]
+    #[ignore]
+    fn listener_child() {
+        if std::env::var_os(LISTENER_ENV).is_none() {
+            return;
+        }
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        println!("PORT={}", listener.local_addr().unwrap().port());
+        thread::sleep(Duration::from_secs(30));
+    }
+
+    /// 以子进程方式运行 `listener_child`，返回子进程与其监听的端口
+    fn spawn_listener() -> (std::process::Child, u16) {
+        use std::io::{BufRead, BufReader};
+
+        let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--ignored",
+                "--exact",
+                "process::tests::listener_child",
+                "--nocapture",
+            ])
+            .env(LISTENER_ENV, "1")
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let port = BufReader::new(child.stdout.take().unwrap())
+            .lines()
+            .map_while(Result::ok)
+            // 测试框架在同一行先输出测试名
+            .find_map(|line| line.rsplit_once("PORT=")?.1.parse::<u16>().ok())
+            .unwrap();
+        (child, port)
+    }
+
+    #[test]
+    fn dry_run_leaves_the_listener_running() {
+        let (mut child, port) = spawn_listener();
+        let options = KillOptions {
+            grace: Duration::ZERO,
+            allow_self: false,
+            backends: Vec::new(),
+            include_children: true,
+            expected_names: None,
+            protocol: Protocol::Tcp,
+            dry_run: true,
+        };
+        let summary = kill_port_listeners(port, &options).unwrap();
+        let targets: Vec<u32> = summary.targets.iter().map(|process| process.pid).collect();
+        assert_eq!(targets, vec![child.id()]);
+        assert!(summary.killed.is_empty());
+        assert!(summary.skipped.is_empty());
+
+        thread::sleep(KILL_POLL_INTERVAL);
+        assert!(child.try_wait().unwrap().is_none());
+        assert!(process_alive(child.id()));
+        assert!(!can_bind(port));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kill_tree_ends_the_shell_and_its_background_child() {
+        let shell = std::process::Command::new("sh")
+            .args(["-c", "sleep 60 & wait"])
+            .spawn()
+            .unwrap();
+        let root = shell.id();
+        let reaper = thread::spawn(move || {
+            let mut shell = shell;
+            shell.wait()
+        });
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let tree = loop {
+            let tree = tree_kill_order(&[root]);
+            if tree.len() == 2 {
+                break tree;
+            }
+            assert!(Instant::now() < deadline, "sleep 未启动: {tree:?}");
+            thread::sleep(KILL_POLL_INTERVAL);
+        };
+        // 子进程优先
+        assert_eq!(tree[1], root);
+        let sleep = tree[0];
+
+        // sleep 结束后 sh 的 wait 随即返回，sh 可能在收到信号前已自行退出
+        let signalled = kill_tree(root, false, Duration::from_secs(5)).unwrap();
+        assert_eq!(signalled[0], sleep);
+        assert!(signalled.iter().all(|pid| *pid == sleep || *pid == root));
+        reaper.join().unwrap().unwrap();
+        // 孤儿进程由 init 回收，稍等片刻
+        wait_for_exit(&[sleep], Duration::from_secs(5));
+        assert!(!process_alive(root));
+        assert!(!process_alive(sleep));
+    }
+
+    #[test]
+    fn taskkill_exit_codes_are_mapped() {
+        assert!(taskkill_result(42, Some(0)).is_ok());
+        assert!(matches!(
+            taskkill_result(42, Some(5)),
+            Err(PortError::PermissionDenied { pid: 42 })
+        ));
+        // 128 是 taskkill 对“找不到进程”的返回值，按进程不存在处理而非命令失败
+        assert!(matches!(
+            taskkill_result(42, Some(128)),
+            Err(PortError::NoSuchProcess { pid: 42 })
+        ));
+        assert!(matches!(
+            taskkill_result(42, Some(1)),
+            Err(PortError::CommandFailed { ref tool, code: 1 }) if tool == "taskkill"
+        ));
+        assert!(matches!(
+            taskkill_result(42, None),
+            Err(PortError::CommandFailed { code: -1, .. })
+        ));
+    }
+
+    #[test]
+    fn listening_ports_include_our_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let entries = listening_ports().unwrap();
+        let ours: Vec<_> = entries.iter().filter(|entry| entry.port == port).collect();
+        assert_eq!(ours.len(), 1, "{entries:?}");
+        assert_eq!(ours[0].pid, std::process::id());
+        assert!(!ours[0].process_name.is_empty());
+        assert!(entries.windows(2).all(|pair| pair[0].port <= pair[1].port));
+        drop(listener);
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, TcpListener};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use tauri::async_runtime;
+use tauri::{AppHandle, Manager};
+use tokio::time::sleep;
+
+use crate::ports::PortError;
+
+/// 未指定 TTL 时预留的默认保留时长
+pub(crate) const DEFAULT_RESERVATION_TTL: Duration = Duration::from_secs(30);
+
+struct Reservation {
+    port: u16,
+    // 仅用于占住端口，drop 即释放
+    _listener: TcpListener,
+}
+
+#[derive(Default)]
+struct Reservations {
+    next_id: u32,
+    entries: HashMap<u32, Reservation>,
+}
+
+/// 启动后端前预留的端口，通过 `.manage()` 注册为全局状态
+#[derive(Default)]
+pub(crate) struct PortReservations {
+    inner: Mutex<Reservations>,
+}
+
+impl PortReservations {
+    fn lock(&self) -> MutexGuard<'_, Reservations> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn insert(&self, port: u16) -> Result<u32, PortError> {
+        let mut reservations = self.lock();
+        if reservations
+            .entries
+            .values()
+            .any(|reservation| reservation.port == port)
+        {
+            return Err(PortError::PortAlreadyReserved { port });
+        }
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .map_err(|_| PortError::PortInUse { port })?;
+
+        reservations.next_id = reservations.next_id.wrapping_add(1);
+        let id = reservations.next_id;
+        reservations.entries.insert(
+            id,
+            Reservation {
+                port,
+                _listener: listener,
+            },
+        );
+        Ok(id)
+    }
+
+    /// 释放预留并返回对应端口
+    pub(crate) fn release(&self, id: u32) -> Result<u16, PortError> {
+        self.lock()
+            .entries
+            .remove(&id)
+            .map(|reservation| reservation.port)
+            .ok_or(PortError::ReservationNotFound { id })
+    }
+}
+
+/// 在回环地址上监听 `port` 直到 `release` 或 TTL 到期，返回预留 ID；
+/// 到期自动释放，避免前端崩溃后端口一直被占用
+pub(crate) fn reserve(app: &AppHandle, port: u16, ttl: Duration) -> Result<u32, PortError> {
+    let id = app.state::<PortReservations>().insert(port)?;
+
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        sleep(ttl).await;
+        // 已被主动释放时忽略
+        let _ = app.state::<PortReservations>().release(id);
+    });
+    Ok(id)
+}
@@ -0,0 +1,179 @@
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tracing::info;
+
+use crate::ports::PortError;
+#[cfg(desktop)]
+use crate::window_state;
+use crate::MAIN_WINDOW;
+
+/// 次级窗口的类别、前端路由与标题；窗口 label 为 `<类别>-<序号>`，需要与 `capabilities/default.json`
+/// 中的窗口匹配规则保持一致
+const WINDOW_KINDS: &[(&str, &str, &str)] =
+    &[("review", "/review", "评审"), ("diff", "/diff", "对比")];
+/// 没有保存的窗口状态时的默认内容区大小（逻辑像素）
+#[cfg(desktop)]
+const DEFAULT_SIZE: (f64, f64) = (1000.0, 720.0);
+#[cfg(desktop)]
+const MIN_SIZE: (f64, f64) = (600.0, 400.0);
+/// 前端在次级窗口中通过该全局变量读取 [`WindowInit`]，页面刷新后仍然存在
+const INIT_GLOBAL: &str = "__OPEN_REVIEW_WINDOW__";
+
+/// 页面脚本执行前注入的窗口信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WindowInit<'a> {
+    label: &'a str,
+    kind: &'a str,
+    context: &'a Value,
+}
+
+/// `list_windows` 的结果项；`kind` 为 `None` 表示主窗口
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowInfo {
+    pub label: String,
+    pub kind: Option<String>,
+    pub title: String,
+    pub visible: bool,
+    pub focused: bool,
+}
+
+/// 由 label 得到次级窗口的类别与序号，不是次级窗口时返回 `None`
+fn parse_label(label: &str) -> Option<(&'static str, u32)> {
+    let (kind, index) = label.rsplit_once('-')?;
+    let index = index.parse::<u32>().ok()?;
+    WINDOW_KINDS
+        .iter()
+        .find(|(known, _, _)| *known == kind)
+        .map(|(known, _, _)| (*known, index))
+}
+
+fn kind_of(label: &str) -> Option<&'static str> {
+    parse_label(label).map(|(kind, _)| kind)
+}
+
+/// `list` 的排序键：主窗口在前，次级窗口按类别与数字序号排列（`review-2` 在 `review-10` 之前）
+fn sort_key(label: &str) -> (bool, Option<(&'static str, u32)>, &str) {
+    (label != MAIN_WINDOW, parse_label(label), label)
+}
+
+/// 取该类别未被占用的最小序号，关闭后重新打开的窗口沿用之前保存的位置与大小
+fn next_label(app: &AppHandle, kind: &str) -> String {
+    let windows = app.webview_windows();
+    (1..)
+        .map(|index| format!("{kind}-{index}"))
+        .find(|label| !windows.contains_key(label))
+        .unwrap_or_default()
+}
+
+fn failed(error: impl ToString) -> PortError {
+    PortError::WindowFailed {
+        reason: error.to_string(),
+    }
+}
+
+/// 打开 `kind` 类别的次级窗口并返回其 label；`context` 在页面脚本执行前注入为
+/// `window.__OPEN_REVIEW_WINDOW__.context`。窗口有保存的状态时按原位置恢复，否则居中显示
+#[cfg_attr(mobile, allow(unused_variables))]
+pub(crate) fn open(app: &AppHandle, kind: &str, context: &Value) -> Result<String, PortError> {
+    let Some(&(kind, route, title)) = WINDOW_KINDS.iter().find(|(known, _, _)| *known == kind)
+    else {
+        return Err(PortError::UnknownWindowKind {
+            kind: kind.to_string(),
+        });
+    };
+    let label = next_label(app, kind);
+    let init = WindowInit {
+        label: &label,
+        kind,
+        context,
+    };
+    let script = format!(
+        "window.{INIT_GLOBAL} = {};",
+        serde_json::to_string(&init).map_err(failed)?
+    );
+    let builder = WebviewWindowBuilder::new(app, &label, WebviewUrl::App(route.into()))
+        .initialization_script(&script);
+    // 先隐藏创建，恢复位置后再显示，避免窗口在默认位置闪现
+    #[cfg(desktop)]
+    let builder = builder
+        .title(format!("{} - {title}", app.package_info().name))
+        .inner_size(DEFAULT_SIZE.0, DEFAULT_SIZE.1)
+        .min_inner_size(MIN_SIZE.0, MIN_SIZE.1)
+        .center()
+        .visible(false);
+    let window = builder.build().map_err(failed)?;
+    #[cfg(desktop)]
+    {
+        window_state::restore_saved(&window);
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    info!(%label, "打开次级窗口");
+    Ok(window.label().to_string())
+}
+
+/// 关闭 `label` 窗口；主窗口同样经过 `CloseRequested`，可能按设置隐藏到托盘
+pub(crate) fn close(app: &AppHandle, label: &str) -> Result<(), PortError> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| PortError::WindowNotFound {
+            label: label.to_string(),
+        })?;
+    window.close().map_err(failed)
+}
+
+fn describe(label: String, window: &WebviewWindow) -> WindowInfo {
+    WindowInfo {
+        kind: kind_of(&label).map(String::from),
+        title: window.title().unwrap_or_default(),
+        visible: window.is_visible().unwrap_or(false),
+        focused: window.is_focused().unwrap_or(false),
+        label,
+    }
+}
+
+/// 所有已打开的窗口，主窗口在前，其余按类别与序号排序
+pub(crate) fn list(app: &AppHandle) -> Vec<WindowInfo> {
+    let mut windows: Vec<WindowInfo> = app
+        .webview_windows()
+        .into_iter()
+        .map(|(label, window)| describe(label, &window))
+        .collect();
+    windows.sort_by(|a, b| sort_key(&a.label).cmp(&sort_key(&b.label)));
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_sort_by_kind_then_numeric_index() {
+        let mut labels = vec![
+            "review-10",
+            "diff-2",
+            "review-2",
+            "main",
+            "review-1",
+            "diff-10",
+        ];
+        labels.sort_by_key(|label| sort_key(label));
+        assert_eq!(
+            labels,
+            vec![
+                "main",
+                "diff-2",
+                "diff-10",
+                "review-1",
+                "review-2",
+                "review-10"
+            ]
+        );
+        assert_eq!(parse_label("review-3"), Some(("review", 3)));
+        assert_eq!(parse_label("review-x"), None);
+        assert_eq!(parse_label("settings-1"), None);
+    }
+}
@@ -0,0 +1,323 @@
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+use tracing::{info, warn};
+
+use crate::ports::PortError;
+use crate::{store_failed, STORE_PATH};
+
+const MAX_KEY_LEN: usize = 256;
+/// 前端保存应用配置的键，其中 `secrets` 下是 GitLab token 与各供应商的 API Key
+const APP_CONFIG_KEY: &str = "app_config";
+const GITLAB_TOKEN_SECRET: &str = "gitlab_token";
+const PROVIDER_KEY_PREFIX: &str = "provider_api_key.";
+/// 已移入钥匙串的取值在 store 中的 `mode`，`value` 为钥匙串中的键名
+const KEYCHAIN_MODE: &str = "keychain";
+/// 检测 Secret Service 是否可用时读取的键，不存在也说明服务可用
+#[cfg(target_os = "linux")]
+const PROBE_KEY: &str = "__probe__";
+/// 没有 Secret Service 时使用的加密文件，位于应用数据目录
+#[cfg(target_os = "linux")]
+const SECRETS_FILE_NAME: &str = "secrets.enc.json";
+#[cfg(target_os = "linux")]
+const MACHINE_ID_PATHS: [&str; 2] = ["/etc/machine-id", "/var/lib/dbus/machine-id"];
+#[cfg(target_os = "linux")]
+const NONCE_LEN: usize = 12;
+
+/// 保存密钥的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+// 每个平台只会构造其中一部分
+#[allow(dead_code)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecretBackend {
+    Keychain,
+    CredentialManager,
+    SecretService,
+    EncryptedFile,
+}
+
+/// 使用的后端在首次访问时确定，运行期间不变
+#[derive(Default)]
+pub struct Secrets {
+    backend: OnceLock<SecretBackend>,
+    /// 串行化加密文件的读改写
+    file: Mutex<()>,
+}
+
+impl Secrets {
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn lock_file(&self) -> MutexGuard<'_, ()> {
+        self.file.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn secret_failed(reason: impl std::fmt::Display) -> PortError {
+    PortError::SecretFailed {
+        reason: reason.to_string(),
+    }
+}
+
+fn validate_key(key: &str) -> Result<(), PortError> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN || key.chars().any(char::is_control) {
+        return Err(secret_failed(format!("无效的密钥名: {key:?}")));
+    }
+    Ok(())
+}
+
+/// 钥匙串条目以应用标识为服务名，不同应用之间互不可见
+fn entry(app: &AppHandle, key: &str) -> Result<keyring::Entry, PortError> {
+    keyring::Entry::new(&app.config().identifier, key).map_err(secret_failed)
+}
+
+/// macOS 与 iOS 使用系统钥匙串
+#[cfg(not(any(windows, target_os = "linux")))]
+fn detect_backend(_app: &AppHandle) -> SecretBackend {
+    SecretBackend::Keychain
+}
+
+#[cfg(windows)]
+fn detect_backend(_app: &AppHandle) -> SecretBackend {
+    SecretBackend::CredentialManager
+}
+
+/// 没有运行 Secret Service（无桌面环境、精简发行版）时改用加密文件
+#[cfg(target_os = "linux")]
+fn detect_backend(app: &AppHandle) -> SecretBackend {
+    match keyring::Entry::new(&app.config().identifier, PROBE_KEY)
+        .and_then(|entry| entry.get_password())
+    {
+        Ok(_) | Err(keyring::Error::NoEntry) => SecretBackend::SecretService,
+        Err(error) => {
+            warn!(%error, "Secret Service 不可用，密钥改为保存在加密文件中");
+            SecretBackend::EncryptedFile
+        }
+    }
+}
+
+pub(crate) fn backend(app: &AppHandle) -> SecretBackend {
+    *app.state::<Secrets>()
+        .backend
+        .get_or_init(|| detect_backend(app))
+}
+
+pub(crate) fn set(app: &AppHandle, key: &str, value: &str) -> Result<(), PortError> {
+    validate_key(key)?;
+    #[cfg(target_os = "linux")]
+    if backend(app) == SecretBackend::EncryptedFile {
+        return file_set(app, key, Some(value));
+    }
+    entry(app, key)?.set_password(value).map_err(secret_failed)
+}
+
+/// 不存在时返回 `None`
+pub(crate) fn get(app: &AppHandle, key: &str) -> Result<Option<String>, PortError> {
+    validate_key(key)?;
+    #[cfg(target_os = "linux")]
+    if backend(app) == SecretBackend::EncryptedFile {
+        return file_get(app, key);
+    }
+    match entry(app, key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(error) => Err(secret_failed(error)),
+    }
+}
+
+/// 不存在时视为成功
+pub(crate) fn delete(app: &AppHandle, key: &str) -> Result<(), PortError> {
+    validate_key(key)?;
+    #[cfg(target_os = "linux")]
+    if backend(app) == SecretBackend::EncryptedFile {
+        return file_set(app, key, None);
+    }
+    match entry(app, key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(error) => Err(secret_failed(error)),
+    }
+}
+
+/// 加密密钥由应用标识与本机 machine-id 派生：文件被复制到其他机器后无法解密，
+/// 但不能防御本机上以同一用户运行的其他程序
+#[cfg(target_os = "linux")]
+fn file_cipher(app: &AppHandle) -> Result<aes_gcm::Aes256Gcm, PortError> {
+    use aes_gcm::aead::KeyInit;
+    use sha2::{Digest, Sha256};
+
+    let machine_id = MACHINE_ID_PATHS
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| secret_failed("无法读取 machine-id"))?;
+    let key = Sha256::digest(format!("{}:{machine_id}", app.config().identifier));
+    aes_gcm::Aes256Gcm::new_from_slice(&key).map_err(secret_failed)
+}
+
+#[cfg(target_os = "linux")]
+fn secrets_file(app: &AppHandle) -> Result<std::path::PathBuf, PortError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(SECRETS_FILE_NAME))
+        .map_err(secret_failed)
+}
+
+/// 键到 `十六进制(nonce + 密文)` 的映射，文件不存在时为空
+#[cfg(target_os = "linux")]
+fn read_secrets_file(
+    path: &std::path::Path,
+) -> Result<std::collections::BTreeMap<String, String>, PortError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(secret_failed),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Default::default()),
+        Err(error) => Err(secret_failed(error)),
+    }
+}
+
+/// 先写临时文件再替换，权限为 0600
+#[cfg(target_os = "linux")]
+fn write_secrets_file(
+    path: &std::path::Path,
+    entries: &std::collections::BTreeMap<String, String>,
+) -> Result<(), PortError> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(secret_failed)?;
+    }
+    let content = serde_json::to_string(entries).map_err(secret_failed)?;
+    let temp = path.with_extension("tmp");
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&temp)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .and_then(|()| std::fs::rename(&temp, path))
+        .map_err(secret_failed)
+}
+
+#[cfg(target_os = "linux")]
+fn file_get(app: &AppHandle, key: &str) -> Result<Option<String>, PortError> {
+    use aes_gcm::aead::Aead;
+
+    let secrets = app.state::<Secrets>();
+    let _guard = secrets.lock_file();
+    let entries = read_secrets_file(&secrets_file(app)?)?;
+    let Some(encoded) = entries.get(key) else {
+        return Ok(None);
+    };
+    let bytes = decode_hex(encoded).ok_or_else(|| secret_failed("加密文件已损坏"))?;
+    if bytes.len() < NONCE_LEN {
+        return Err(secret_failed("加密文件已损坏"));
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let plaintext = file_cipher(app)?
+        .decrypt(aes_gcm::Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| secret_failed("无法解密，文件可能来自其他机器"))?;
+    String::from_utf8(plaintext)
+        .map(Some)
+        .map_err(secret_failed)
+}
+
+/// `value` 为 `None` 时删除
+#[cfg(target_os = "linux")]
+fn file_set(app: &AppHandle, key: &str, value: Option<&str>) -> Result<(), PortError> {
+    use aes_gcm::aead::{Aead, AeadCore, OsRng};
+
+    let secrets = app.state::<Secrets>();
+    let _guard = secrets.lock_file();
+    let path = secrets_file(app)?;
+    let mut entries = read_secrets_file(&path)?;
+    match value {
+        Some(value) => {
+            let nonce = aes_gcm::Aes256Gcm::generate_nonce(&mut OsRng);
+            let ciphertext = file_cipher(app)?
+                .encrypt(&nonce, value.as_bytes())
+                .map_err(secret_failed)?;
+            let mut bytes = nonce.to_vec();
+            bytes.extend(ciphertext);
+            entries.insert(key.to_string(), encode_hex(&bytes));
+        }
+        None if entries.remove(key).is_none() => return Ok(()),
+        None => {}
+    }
+    write_secrets_file(&path, &entries)
+}
+
+#[cfg(target_os = "linux")]
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(target_os = "linux")]
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+/// `secret` 为 `{ mode: "plain", value }` 时把取值存入钥匙串并改写为 `{ mode: "keychain", value: key }`
+fn move_plain_secret(app: &AppHandle, secret: &mut Value, key: &str) -> Result<bool, PortError> {
+    if secret.get("mode").and_then(Value::as_str) != Some("plain") {
+        return Ok(false);
+    }
+    let Some(value) = secret.get("value").and_then(Value::as_str) else {
+        return Ok(false);
+    };
+    set(app, key, value)?;
+    let mut moved = Map::new();
+    moved.insert("mode".to_string(), KEYCHAIN_MODE.into());
+    moved.insert("value".to_string(), key.into());
+    *secret = Value::Object(moved);
+    Ok(true)
+}
+
+/// 启动时执行：把前端以明文保存在 store 中的 GitLab token 与供应商 API Key 移入钥匙串，
+/// 返回移动的数量；部分取值失败时仍保存已移动的取值，再返回第一个错误。
+/// 已移动的取值在 store 中不再是明文，重复执行不会有变化；
+/// 加密保存的取值（`mode: "enc"`）只有前端能解密，由前端在下次保存配置时写入钥匙串
+pub(crate) fn migrate_store_secrets(app: &AppHandle) -> Result<usize, PortError> {
+    let store = app.store(STORE_PATH).map_err(store_failed)?;
+    let Some(mut config) = store.get(APP_CONFIG_KEY) else {
+        return Ok(0);
+    };
+    let Some(secrets) = config.get_mut("secrets") else {
+        return Ok(0);
+    };
+
+    // 单个取值失败时继续处理其余取值，已移入钥匙串的取值必须写回 store，否则会留下明文副本
+    let mut moved = 0;
+    let mut failure = None;
+    let mut migrate = |secret: &mut Value, key: &str| match move_plain_secret(app, secret, key) {
+        Ok(done) => moved += usize::from(done),
+        Err(error) => {
+            warn!(key, %error, "无法将密钥移入钥匙串");
+            failure.get_or_insert(error);
+        }
+    };
+    if let Some(token) = secrets.get_mut("gitlabToken") {
+        migrate(token, GITLAB_TOKEN_SECRET);
+    }
+    if let Some(Value::Object(keys)) = secrets.get_mut("providerApiKeys") {
+        for (provider, secret) in keys.iter_mut() {
+            migrate(secret, &format!("{PROVIDER_KEY_PREFIX}{provider}"));
+        }
+    }
+    if moved > 0 {
+        store.set(APP_CONFIG_KEY, config);
+        store.save().map_err(store_failed)?;
+        info!(moved, backend = ?backend(app), "已将明文保存的密钥移入钥匙串");
+    }
+    failure.map_or(Ok(moved), Err)
+}
@@ -0,0 +1,738 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_store::{Store, StoreExt};
+use tracing::{info, warn};
+
+use crate::backend::{
+    is_secret_env, mark_restart_required, running_backends, set_auto_restart_enabled,
+};
+use crate::backend_log::now_millis;
+use crate::notifications::NotificationCategory;
+use crate::ports::PortError;
+use crate::{store_failed, DEFAULT_BACKEND_PORT, STORE_PATH};
+
+/// 全部设置作为一个对象保存在该键下，每次整体写入，不会出现只保存了一部分的情况
+const SETTINGS_KEY: &str = "settings";
+/// 存储结构的版本号；缺失时按内容推断
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+/// 依次执行的迁移，第 `i` 项把版本 `i + 1` 升级到 `i + 2`。每项都必须可以重复执行：
+/// 迁移中途失败时版本号不会更新，下次启动会从头再来
+const MIGRATIONS: &[fn(&mut Document)] = &[migrate_v1_to_v2];
+/// 当前的存储结构版本
+const SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64 + 1;
+/// 启动时完成迁移后发送，载荷为 [`SettingsMigrated`]
+pub(crate) const SETTINGS_MIGRATED_EVENT: &str = "settings://migrated";
+/// 设置变化后发送给所有窗口，每次更新只发送一次，载荷为 [`SettingsChanged`]
+pub(crate) const SETTINGS_CHANGED_EVENT: &str = "settings://changed";
+const EXPORT_FILE_NAME: &str = "open-reviewer-settings.json";
+/// 版本 1 逐项保存的键
+const LEGACY_PORT_KEY: &str = "backend_port";
+/// 更早的版本 1 保存端口的键，`backend_port` 不存在时才使用
+const LEGACY_SERVER_PORT_KEY: &str = "serverPort";
+const LEGACY_ENV_KEY: &str = "backend.env";
+const LEGACY_KEEP_BACKEND_KEY: &str = "keep_backend_on_exit";
+/// 代理地址允许的协议
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// 日志级别，只作用于本应用自身；依赖库最多输出到 info
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    #[default]
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+
+    /// 对应的 `EnvFilter` 规则
+    pub(crate) fn filter(self) -> String {
+        let dependencies = self.min(Self::Info);
+        format!("{},temp_init_lib={}", dependencies.as_str(), self.as_str())
+    }
+}
+
+/// 应用设置；缺失的字段取默认值，出现未知字段时拒绝
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct Settings {
+    /// 默认工作区的后端端口，也是未指定端口的后端分配端口的起点
+    pub backend_port: u16,
+    /// 默认工作区后端的数据目录，未设置时由后端使用自身默认目录；必须是绝对路径
+    pub data_dir: Option<PathBuf>,
+    /// 意外退出后是否自动重启；修改后同时作用于正在运行的后端
+    pub auto_restart: bool,
+    /// 修改后立即生效；设置了 `RUST_LOG` 时以其为准
+    pub log_level: LogLevel,
+    /// 以 `HTTP_PROXY`/`HTTPS_PROXY` 注入后端，`backend_env` 中已有同名变量时不覆盖
+    pub proxy: Option<String>,
+    /// 为 `true` 时应用退出后保留后端进程
+    pub keep_backend_on_exit: bool,
+    /// 为 `true` 时关闭主窗口只隐藏到托盘；托盘不可用时仍直接退出
+    pub minimize_to_tray: bool,
+    /// 为 `true` 时关闭主窗口前先停止所有后端并等待其退出，不受 `keep_backend_on_exit` 影响；
+    /// 隐藏到托盘时不停止
+    pub stop_backend_on_close: bool,
+    /// 为 `false` 时不发出任何系统通知
+    pub notifications_enabled: bool,
+    /// 值为 `true` 的类别不发出通知，未列出的类别照常发出
+    pub muted_notifications: HashMap<NotificationCategory, bool>,
+    /// 全局快捷键，ID 到快捷键（如 `CommandOrControl+Shift+O`）；修改时先注销旧快捷键再注册新的
+    pub shortcuts: HashMap<String, String>,
+    /// 拖入窗口时导入为附件的文件扩展名，不区分大小写；`pdf` 还会检查文件头
+    pub import_extensions: Vec<String>,
+    /// 启动后端时注入的环境变量
+    pub backend_env: HashMap<String, String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            backend_port: DEFAULT_BACKEND_PORT,
+            data_dir: None,
+            auto_restart: true,
+            log_level: LogLevel::default(),
+            proxy: None,
+            keep_backend_on_exit: false,
+            minimize_to_tray: false,
+            stop_backend_on_close: false,
+            notifications_enabled: true,
+            muted_notifications: HashMap::new(),
+            shortcuts: HashMap::new(),
+            import_extensions: vec!["pdf".to_string()],
+            backend_env: HashMap::new(),
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> PortError {
+    PortError::InvalidSettings {
+        reason: reason.into(),
+    }
+}
+
+impl Settings {
+    /// 检查取值范围；类型不对与未知字段在反序列化时就已拒绝
+    pub(crate) fn validate(&self) -> Result<(), PortError> {
+        if self.backend_port == 0 {
+            return Err(invalid("backendPort 不能为 0"));
+        }
+        if self.data_dir.as_ref().is_some_and(|dir| !dir.is_absolute()) {
+            return Err(invalid("dataDir 必须是绝对路径"));
+        }
+        if let Some(proxy) = &self.proxy {
+            let valid = proxy
+                .split_once("://")
+                .is_some_and(|(scheme, host)| PROXY_SCHEMES.contains(&scheme) && !host.is_empty());
+            if !valid {
+                return Err(invalid(format!(
+                    "proxy 必须形如 http://host:port，支持 {}",
+                    PROXY_SCHEMES.join("、")
+                )));
+            }
+        }
+        if let Some(key) = self
+            .backend_env
+            .keys()
+            .find(|key| key.is_empty() || key.contains(['=', '\0']))
+        {
+            return Err(invalid(format!("backendEnv 中的变量名无效: {key:?}")));
+        }
+        if self.shortcuts.keys().any(|id| id.trim().is_empty()) {
+            return Err(invalid("shortcuts 的 ID 不能为空"));
+        }
+        if self
+            .import_extensions
+            .iter()
+            .any(|extension| extension.trim_start_matches('.').trim().is_empty())
+        {
+            return Err(invalid("importExtensions 中的扩展名不能为空"));
+        }
+        Ok(())
+    }
+
+    /// 解析并校验一份完整的设置，缺失的字段取默认值
+    pub(crate) fn parse(value: Value) -> Result<Self, PortError> {
+        let settings: Self =
+            serde_json::from_value(value).map_err(|error| invalid(error.to_string()))?;
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+/// 按 JSON Merge Patch（RFC 7396）合并：对象逐键合并，`null` 删除该键（即恢复默认值）
+fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Some(object) = target.as_object_mut() else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            object.remove(&key);
+        } else {
+            merge_patch(object.entry(key).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// `settings://migrated` 事件载荷
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsMigrated {
+    pub from: u64,
+    pub to: u64,
+}
+
+/// `import_settings` / `restore_settings_backup` 的结果；`changed` 为取值发生变化的设置项（camelCase 键名）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImport {
+    pub settings: Settings,
+    pub changed: Vec<String>,
+}
+
+/// `reset_settings` 的结果；`backup` 为重置前整个设置存储的备份文件，可交给 `restore_settings_backup` 恢复
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsReset {
+    pub settings: Settings,
+    pub changed: Vec<String>,
+    pub backup: String,
+}
+
+/// `settings://changed` 事件载荷：变化的设置项（camelCase 键名）及其新值
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChanged {
+    pub changed_keys: Vec<String>,
+    pub new_values: Map<String, Value>,
+}
+
+/// 设置存储的全部键值；迁移在它上面进行，导出文件也是同样的结构，
+/// 因此导入时可以走同一条迁移流程
+type Document = Map<String, Value>;
+
+fn store_document(store: &Store<Wry>) -> Document {
+    store.entries().into_iter().collect()
+}
+
+/// 从版本 1 逐项保存的键构造设置，无效的取值直接忽略
+fn legacy_settings(document: &Document) -> Settings {
+    let defaults = Settings::default();
+    let backend_port = document
+        .get(LEGACY_PORT_KEY)
+        .or_else(|| document.get(LEGACY_SERVER_PORT_KEY))
+        .and_then(|value| value.as_u64())
+        .and_then(|value| u16::try_from(value).ok())
+        .filter(|port| *port != 0)
+        .unwrap_or(defaults.backend_port);
+    let backend_env = match document.get(LEGACY_ENV_KEY) {
+        Some(Value::Object(entries)) => entries
+            .iter()
+            .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+            .collect(),
+        _ => HashMap::new(),
+    };
+    let keep_backend_on_exit = document
+        .get(LEGACY_KEEP_BACKEND_KEY)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(defaults.keep_backend_on_exit);
+    Settings {
+        backend_port,
+        keep_backend_on_exit,
+        backend_env,
+        ..defaults
+    }
+}
+
+/// 读取设置；存储不可用或内容损坏时记录警告并使用默认值，不会因此影响启动
+pub(crate) fn load(app: &AppHandle) -> Settings {
+    let store = match app.store(STORE_PATH) {
+        Ok(store) => store,
+        Err(error) => {
+            warn!(%error, "无法打开设置存储，使用默认设置");
+            return Settings::default();
+        }
+    };
+    // 迁移失败时仍按版本 1 读取，不丢失用户的设置
+    let Some(value) = store.get(SETTINGS_KEY) else {
+        return legacy_settings(&store_document(&store));
+    };
+    Settings::parse(value).unwrap_or_else(|error| {
+        warn!(%error, "保存的设置无效，使用默认设置");
+        Settings::default()
+    })
+}
+
+/// 校验后整体写入设置
+pub(crate) fn save(app: &AppHandle, settings: &Settings) -> Result<(), PortError> {
+    settings.validate()?;
+    let value = serde_json::to_value(settings).map_err(|error| invalid(error.to_string()))?;
+    let store = app.store(STORE_PATH).map_err(store_failed)?;
+    store.set(SETTINGS_KEY, value);
+    store.save().map_err(store_failed)
+}
+
+/// 把 `patch` 合并到当前设置上并校验，不保存
+pub(crate) fn apply_patch(current: &Settings, patch: Value) -> Result<Settings, PortError> {
+    if !patch.is_object() {
+        return Err(invalid("设置补丁必须是 JSON 对象"));
+    }
+    let mut value = serde_json::to_value(current).map_err(|error| invalid(error.to_string()))?;
+    merge_patch(&mut value, patch);
+    Settings::parse(value)
+}
+
+/// 版本 1 逐项保存、迁移后删除的键
+const LEGACY_KEYS: [&str; 4] = [
+    LEGACY_PORT_KEY,
+    LEGACY_SERVER_PORT_KEY,
+    LEGACY_ENV_KEY,
+    LEGACY_KEEP_BACKEND_KEY,
+];
+
+/// 版本 1 → 2：逐项保存的键合并为 `settings` 对象，`serverPort` / `backend_port` 改为 `backendPort`
+fn migrate_v1_to_v2(document: &mut Document) {
+    if !document.contains_key(SETTINGS_KEY) {
+        if let Ok(value) = serde_json::to_value(legacy_settings(document)) {
+            document.insert(SETTINGS_KEY.to_string(), value);
+        }
+    }
+    for key in LEGACY_KEYS {
+        document.remove(key);
+    }
+}
+
+/// 版本 1 没有版本号；版本 2 最初发布时也没有写入版本号，以是否存在 `settings` 区分
+fn stored_version(document: &Document) -> u64 {
+    match document
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(|value| value.as_u64())
+    {
+        Some(version) => version.max(1),
+        None if document.contains_key(SETTINGS_KEY) => 2,
+        None => 1,
+    }
+}
+
+/// 从 `from` 版本依次执行迁移到当前版本
+fn run_migrations(document: &mut Document, from: u64) {
+    for migration in &MIGRATIONS[(from.max(1) - 1) as usize..] {
+        migration(document);
+    }
+    document.insert(SCHEMA_VERSION_KEY.to_string(), SCHEMA_VERSION.into());
+}
+
+/// 迁移前把设置文件复制为 `settings.json.v<版本>.bak`
+fn backup_store(app: &AppHandle, version: u64) -> Result<(), PortError> {
+    let Ok(dir) = app.path().app_data_dir() else {
+        return Ok(());
+    };
+    let source = dir.join(STORE_PATH);
+    if !source.is_file() {
+        return Ok(());
+    }
+    let target = dir.join(format!("{STORE_PATH}.v{version}.bak"));
+    fs::copy(&source, &target).map(drop).map_err(file_failed)
+}
+
+fn file_failed(error: std::io::Error) -> PortError {
+    PortError::StoreFailed {
+        source: error.to_string(),
+    }
+}
+
+/// 在读取设置前把存储升级到当前版本：先备份，再依次执行迁移，最后写入版本号并发送
+/// `settings://migrated`。新安装只写入版本号；版本号比当前程序新（降级安装）时保持原样
+pub(crate) fn migrate(app: &AppHandle) -> Result<Option<SettingsMigrated>, PortError> {
+    let store = app.store(STORE_PATH).map_err(store_failed)?;
+    if store.is_empty() {
+        store.set(SCHEMA_VERSION_KEY, SCHEMA_VERSION);
+        store.save().map_err(store_failed)?;
+        return Ok(None);
+    }
+    let mut document = store_document(&store);
+    let from = stored_version(&document);
+    if from > SCHEMA_VERSION {
+        warn!(
+            from,
+            current = SCHEMA_VERSION,
+            "设置来自更新的版本，跳过迁移"
+        );
+        return Ok(None);
+    }
+    if from == SCHEMA_VERSION {
+        return Ok(None);
+    }
+
+    backup_store(app, from)?;
+    run_migrations(&mut document, from);
+    for key in store.keys() {
+        if !document.contains_key(&key) {
+            store.delete(&key);
+        }
+    }
+    for (key, value) in document {
+        store.set(key, value);
+    }
+    store.save().map_err(store_failed)?;
+
+    let migrated = SettingsMigrated {
+        from,
+        to: SCHEMA_VERSION,
+    };
+    info!(from, to = SCHEMA_VERSION, "设置已迁移");
+    let _ = app.emit(SETTINGS_MIGRATED_EVENT, migrated.clone());
+    Ok(Some(migrated))
+}
+
+/// 取值发生变化的设置项（camelCase 键名）及其新值
+fn diff(before: &Settings, after: &Settings) -> Map<String, Value> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Map::new();
+    };
+    after
+        .into_iter()
+        .filter(|(key, value)| before.get(key) != Some(value))
+        .collect()
+}
+
+/// Rust 侧对设置变化的响应，不需要等前端再调用命令
+fn apply_change(app: &AppHandle, before: &Settings, after: &Settings) {
+    if after.backend_env != before.backend_env || after.proxy != before.proxy {
+        mark_restart_required(app);
+    }
+    if after.log_level != before.log_level {
+        crate::logging::set_level(app, after.log_level);
+    }
+    if after.auto_restart != before.auto_restart {
+        for backend in running_backends(app) {
+            set_auto_restart_enabled(app, &backend.workspace_id, after.auto_restart);
+        }
+    }
+}
+
+/// 整体保存新设置，应用到 Rust 侧并发送一次 `settings://changed`，返回变化的设置项
+pub(crate) fn replace(
+    app: &AppHandle,
+    current: &Settings,
+    updated: &Settings,
+) -> Result<Vec<String>, PortError> {
+    let new_values = diff(current, updated);
+    if new_values.is_empty() {
+        return Ok(Vec::new());
+    }
+    // 快捷键注册失败时不保存，其余设置也保持不变
+    if updated.shortcuts != current.shortcuts {
+        crate::shortcuts::sync(app, &updated.shortcuts)?;
+    }
+    if let Err(error) = save(app, updated) {
+        if updated.shortcuts != current.shortcuts {
+            let _ = crate::shortcuts::sync(app, &current.shortcuts);
+        }
+        return Err(error);
+    }
+    apply_change(app, current, updated);
+
+    let changed_keys: Vec<String> = new_values.keys().cloned().collect();
+    let _ = app.emit(
+        SETTINGS_CHANGED_EVENT,
+        SettingsChanged {
+            changed_keys: changed_keys.clone(),
+            new_values,
+        },
+    );
+    Ok(changed_keys)
+}
+
+/// 去掉代理地址中的用户名与密码
+fn strip_credentials(proxy: &str) -> String {
+    let Some((scheme, rest)) = proxy.split_once("://") else {
+        return proxy.to_string();
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) => format!("{scheme}://{}", &rest[at + 1..]),
+        None => proxy.to_string(),
+    }
+}
+
+/// 导出时去掉的敏感信息：`*_KEY` / `*_TOKEN` 等环境变量与代理地址中的凭据
+fn without_secrets(settings: &Settings) -> Settings {
+    Settings {
+        proxy: settings.proxy.as_deref().map(strip_credentials),
+        backend_env: settings
+            .backend_env
+            .iter()
+            .filter(|(key, _)| !is_secret_env(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        ..settings.clone()
+    }
+}
+
+/// 把当前设置（不含敏感信息）与存储结构版本写入 `path`
+pub(crate) fn export(app: &AppHandle, path: &Path) -> Result<(), PortError> {
+    let settings = without_secrets(&load(app));
+    let mut document = Document::new();
+    document.insert(SCHEMA_VERSION_KEY.to_string(), SCHEMA_VERSION.into());
+    document.insert(
+        SETTINGS_KEY.to_string(),
+        serde_json::to_value(&settings).map_err(|error| invalid(error.to_string()))?,
+    );
+    let content =
+        serde_json::to_string_pretty(&document).map_err(|error| invalid(error.to_string()))?;
+    fs::write(path, content).map_err(file_failed)
+}
+
+/// 解析导出文件（也接受旧版本的 `settings.json`），按需迁移后校验；任何一步失败都不会修改现有设置
+fn parse_import(content: &str) -> Result<Settings, PortError> {
+    let Ok(Value::Object(mut document)) = serde_json::from_str::<Value>(content) else {
+        return Err(invalid("文件不是 JSON 对象"));
+    };
+    let has_settings = std::iter::once(SETTINGS_KEY)
+        .chain(LEGACY_KEYS)
+        .any(|key| document.contains_key(key));
+    if !has_settings {
+        return Err(invalid("文件中没有设置"));
+    }
+    let version = stored_version(&document);
+    if version > SCHEMA_VERSION {
+        return Err(invalid(format!(
+            "文件来自更新的版本（结构版本 {version}），当前只支持到 {SCHEMA_VERSION}"
+        )));
+    }
+    run_migrations(&mut document, version);
+    Settings::parse(document.remove(SETTINGS_KEY).unwrap_or_default())
+}
+
+/// 从 `path` 导入设置并整体替换现有设置。
+/// 导出时去掉的敏感环境变量在导入文件中缺失时保留本机的取值
+pub(crate) fn import(app: &AppHandle, path: &Path) -> Result<SettingsImport, PortError> {
+    let content = fs::read_to_string(path).map_err(file_failed)?;
+    let mut imported = parse_import(&content)?;
+    let current = load(app);
+    for (key, value) in &current.backend_env {
+        if is_secret_env(key) && !imported.backend_env.contains_key(key) {
+            imported.backend_env.insert(key.clone(), value.clone());
+        }
+    }
+    let changed = replace(app, &current, &imported)?;
+    info!(?changed, path = %path.display(), "已导入设置");
+    Ok(SettingsImport {
+        settings: imported,
+        changed,
+    })
+}
+
+/// 把当前存储的全部内容写入应用数据目录下的 `settings.json.reset-<毫秒时间戳>.bak`
+fn backup_before_reset(app: &AppHandle) -> Result<PathBuf, PortError> {
+    let store = app.store(STORE_PATH).map_err(store_failed)?;
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| PortError::StoreFailed {
+            source: error.to_string(),
+        })?;
+    fs::create_dir_all(&dir).map_err(file_failed)?;
+    let path = dir.join(format!("{STORE_PATH}.reset-{}.bak", now_millis()));
+    let content = serde_json::to_string_pretty(&store_document(&store))
+        .map_err(|error| invalid(error.to_string()))?;
+    fs::write(&path, content).map_err(file_failed)?;
+    Ok(path)
+}
+
+/// 把 `scope` 中的设置项（camelCase 键名）恢复默认值，未指定时恢复全部设置；
+/// 先备份整个存储，含未知设置项时返回 `InvalidSettings` 且不做任何修改
+pub(crate) fn reset(
+    app: &AppHandle,
+    scope: Option<Vec<String>>,
+) -> Result<SettingsReset, PortError> {
+    let current = load(app);
+    let updated = match scope {
+        None => Settings::default(),
+        Some(keys) => {
+            let known = serde_json::to_value(Settings::default())
+                .map_err(|error| invalid(error.to_string()))?;
+            let mut patch = Map::new();
+            for key in keys {
+                if known.get(&key).is_none() {
+                    return Err(invalid(format!("未知的设置项 {key}")));
+                }
+                patch.insert(key, Value::Null);
+            }
+            apply_patch(&current, Value::Object(patch))?
+        }
+    };
+    let backup = backup_before_reset(app)?;
+    let changed = replace(app, &current, &updated)?;
+    info!(?changed, backup = %backup.display(), "已重置设置");
+    Ok(SettingsReset {
+        settings: updated,
+        changed,
+        backup: backup.to_string_lossy().into_owned(),
+    })
+}
+
+/// 从 `reset` 生成的备份恢复设置，按导入文件的规则迁移、校验后整体替换；
+/// 备份中的敏感环境变量原样恢复，校验失败时不修改任何设置
+pub(crate) fn restore_backup(app: &AppHandle, path: &Path) -> Result<SettingsImport, PortError> {
+    let content = fs::read_to_string(path).map_err(file_failed)?;
+    let restored = parse_import(&content)?;
+    let current = load(app);
+    let changed = replace(app, &current, &restored)?;
+    info!(?changed, path = %path.display(), "已从备份恢复设置");
+    Ok(SettingsImport {
+        settings: restored,
+        changed,
+    })
+}
+
+/// 弹出保存对话框选择导出位置，用户取消时返回 `None`
+pub(crate) fn pick_export_path(app: &AppHandle) -> Option<PathBuf> {
+    app.dialog()
+        .file()
+        .add_filter("JSON", &["json"])
+        .set_file_name(EXPORT_FILE_NAME)
+        .blocking_save_file()
+        .and_then(|path| path.into_path().ok())
+}
+
+/// 弹出打开对话框选择要导入的文件，用户取消时返回 `None`
+pub(crate) fn pick_import_path(app: &AppHandle) -> Option<PathBuf> {
+    app.dialog()
+        .file()
+        .add_filter("JSON", &["json"])
+        .blocking_pick_file()
+        .and_then(|path| path.into_path().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn document(value: Value) -> Document {
+        match value {
+            Value::Object(document) => document,
+            other => panic!("fixture 不是对象: {other:?}"),
+        }
+    }
+
+    /// 把一份存储文件按 `migrate` 的流程升级，返回升级后的文档与其中的设置
+    fn upgrade(fixture: Value) -> (Document, Settings) {
+        let mut document = document(fixture);
+        let version = stored_version(&document);
+        run_migrations(&mut document, version);
+        let settings = Settings::parse(document[SETTINGS_KEY].clone()).unwrap();
+        (document, settings)
+    }
+
+    #[test]
+    fn v1_store_is_folded_into_settings() {
+        let (document, settings) = upgrade(json!({
+            "backend_port": 5123,
+            "backend.env": { "RUST_LOG": "debug", "BROKEN": 1 },
+            "keep_backend_on_exit": true,
+            "last_free_port": 5200,
+        }));
+        assert_eq!(
+            settings,
+            Settings {
+                backend_port: 5123,
+                keep_backend_on_exit: true,
+                backend_env: HashMap::from([("RUST_LOG".to_string(), "debug".to_string())]),
+                ..Settings::default()
+            }
+        );
+        assert_eq!(document[SCHEMA_VERSION_KEY], json!(SCHEMA_VERSION));
+        assert!(LEGACY_KEYS.iter().all(|key| !document.contains_key(*key)));
+        // 不属于设置的内部状态原样保留
+        assert_eq!(document["last_free_port"], json!(5200));
+    }
+
+    #[test]
+    fn v1_server_port_is_renamed_to_backend_port() {
+        let (document, settings) = upgrade(json!({ "serverPort": 6001 }));
+        assert_eq!(settings.backend_port, 6001);
+        assert!(!document.contains_key(LEGACY_SERVER_PORT_KEY));
+
+        let (_, settings) = upgrade(json!({ "serverPort": 6001, "backend_port": 6002 }));
+        assert_eq!(settings.backend_port, 6002);
+    }
+
+    #[test]
+    fn v1_invalid_values_fall_back_to_defaults() {
+        for port in [json!(0), json!(70000), json!("5000"), json!(-1)] {
+            let (_, settings) =
+                upgrade(json!({ "backend_port": port, "keep_backend_on_exit": "yes" }));
+            assert_eq!(settings, Settings::default(), "{port}");
+        }
+    }
+
+    #[test]
+    fn v2_store_without_version_is_kept() {
+        let fixture = json!({
+            "settings": { "backendPort": 7000, "autoRestart": false },
+            "backend_port": 5123,
+        });
+        assert_eq!(stored_version(&document(fixture.clone())), 2);
+        let (document, settings) = upgrade(fixture);
+        assert_eq!(settings.backend_port, 7000);
+        assert!(!settings.auto_restart);
+        assert_eq!(document[SCHEMA_VERSION_KEY], json!(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn migrations_are_idempotent() {
+        let (first, _) = upgrade(json!({ "serverPort": 6001, "keep_backend_on_exit": true }));
+        let mut again = first.clone();
+        run_migrations(&mut again, 1);
+        assert_eq!(again, first);
+        assert_eq!(stored_version(&first), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn import_accepts_each_historical_version() {
+        let v1 = parse_import(r#"{ "serverPort": 6001 }"#).unwrap();
+        assert_eq!(v1.backend_port, 6001);
+        let v2 = parse_import(r#"{ "schema_version": 2, "settings": { "backendPort": 7000 } }"#)
+            .unwrap();
+        assert_eq!(v2.backend_port, 7000);
+        assert!(matches!(
+            parse_import(r#"{ "schema_version": 99, "settings": {} }"#),
+            Err(PortError::InvalidSettings { .. })
+        ));
+        assert!(matches!(
+            parse_import(r#"{ "theme": "dark" }"#),
+            Err(PortError::InvalidSettings { .. })
+        ));
+    }
+}
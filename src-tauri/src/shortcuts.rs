@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+#[cfg(desktop)]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+#[cfg(desktop)]
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::AppHandle;
+#[cfg(desktop)]
+use tauri::{async_runtime, Emitter, Manager};
+#[cfg(desktop)]
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+#[cfg(desktop)]
+use tracing::{info, warn};
+
+#[cfg(desktop)]
+use crate::backend::{running_backend, shutdown_backend, DEFAULT_WORKSPACE};
+use crate::ports::PortError;
+use crate::settings;
+#[cfg(desktop)]
+use crate::{run_blocking, tray, DEFAULT_KILL_GRACE_MS};
+
+/// 显示或隐藏主窗口，由 Rust 侧直接处理
+#[cfg(desktop)]
+const TOGGLE_MAIN_WINDOW: &str = "toggle-main-window";
+/// 默认工作区的后端在运行时停止，否则与托盘的“启动后端”一样启动，由 Rust 侧直接处理
+#[cfg(desktop)]
+const TOGGLE_BACKEND: &str = "toggle-backend";
+/// 快捷键按下时发送，载荷为 [`ShortcutTriggered`]；内置操作的快捷键也会发送
+#[cfg(desktop)]
+pub(crate) const SHORTCUT_TRIGGERED_EVENT: &str = "shortcut://triggered";
+
+/// 设置中保存的一个快捷键，`registered` 为 `false` 表示未能向系统注册（例如启动时已被其他程序占用）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub id: String,
+    pub accelerator: String,
+    pub registered: bool,
+}
+
+/// `shortcut://triggered` 事件载荷
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutTriggered {
+    pub id: String,
+    pub accelerator: String,
+}
+
+#[cfg(desktop)]
+#[derive(Debug, Clone)]
+struct Binding {
+    accelerator: String,
+    shortcut: Shortcut,
+}
+
+/// 已向系统注册的快捷键，以 ID 为键，通过 `.manage()` 注册为全局状态
+#[cfg(desktop)]
+#[derive(Default)]
+pub(crate) struct Shortcuts {
+    registered: Mutex<HashMap<String, Binding>>,
+    /// 串行化 `sync`；注册期间不能持有 `registered`，快捷键回调在主线程上读取它
+    syncing: Mutex<()>,
+}
+
+#[cfg(desktop)]
+impl Shortcuts {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Binding>> {
+        self.registered
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(desktop)]
+fn parse(accelerator: &str) -> Result<Shortcut, PortError> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| PortError::InvalidShortcut {
+            accelerator: accelerator.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+#[cfg(desktop)]
+fn conflict(accelerator: &str, reason: impl ToString) -> PortError {
+    PortError::ShortcutConflict {
+        accelerator: accelerator.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// 把已注册的快捷键整体替换为 `bindings`：先注销变化或删除的旧快捷键，再注册新的。
+/// 任一快捷键格式错误、与其他 ID 重复或被系统与其他程序占用时恢复原来的注册并返回错误
+#[cfg(desktop)]
+pub(crate) fn sync(app: &AppHandle, bindings: &HashMap<String, String>) -> Result<(), PortError> {
+    let state = app.state::<Shortcuts>();
+    let _syncing = state.syncing.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let mut wanted = HashMap::new();
+    let mut owners: HashMap<Shortcut, &str> = HashMap::new();
+    for (id, accelerator) in bindings {
+        let shortcut = parse(accelerator)?;
+        if let Some(other) = owners.insert(shortcut, id) {
+            return Err(conflict(accelerator, format!("已分配给 {other}")));
+        }
+        let binding = Binding {
+            accelerator: accelerator.clone(),
+            shortcut,
+        };
+        wanted.insert(id.clone(), binding);
+    }
+
+    let stale: Vec<(String, Binding)> = state
+        .lock()
+        .iter()
+        .filter(|(id, binding)| wanted.get(*id).map(|b| b.shortcut) != Some(binding.shortcut))
+        .map(|(id, binding)| (id.clone(), binding.clone()))
+        .collect();
+    let global = app.global_shortcut();
+    for (id, binding) in &stale {
+        if let Err(error) = global.unregister(binding.shortcut) {
+            warn!(%id, %error, "注销快捷键失败");
+        }
+        state.lock().remove(id);
+    }
+
+    let mut added = Vec::new();
+    for (id, binding) in wanted {
+        // 只是写法不同（如 `Ctrl` 与 `Control`）的同一快捷键不需要重新注册
+        if let Some(existing) = state.lock().get_mut(&id) {
+            existing.accelerator = binding.accelerator;
+            continue;
+        }
+        if let Err(error) = global.register(binding.shortcut) {
+            for (id, shortcut) in added {
+                let _ = global.unregister(shortcut);
+                state.lock().remove(&id);
+            }
+            for (id, binding) in stale {
+                if global.register(binding.shortcut).is_ok() {
+                    state.lock().insert(id, binding);
+                }
+            }
+            return Err(conflict(&binding.accelerator, error));
+        }
+        added.push((id.clone(), binding.shortcut));
+        state.lock().insert(id, binding);
+    }
+    Ok(())
+}
+
+#[cfg(mobile)]
+pub(crate) fn sync(_app: &AppHandle, bindings: &HashMap<String, String>) -> Result<(), PortError> {
+    if bindings.is_empty() {
+        Ok(())
+    } else {
+        Err(PortError::ShortcutsUnsupported)
+    }
+}
+
+/// 启动时逐个注册设置中的快捷键；失败的快捷键只记录警告，不影响其他快捷键
+#[cfg(desktop)]
+pub(crate) fn init(app: &AppHandle) {
+    let state = app.state::<Shortcuts>();
+    for (id, accelerator) in settings::load(app).shortcuts {
+        let result = parse(&accelerator).and_then(|shortcut| {
+            app.global_shortcut()
+                .register(shortcut)
+                .map_err(|e| conflict(&accelerator, e))?;
+            Ok(shortcut)
+        });
+        match result {
+            Ok(shortcut) => {
+                let binding = Binding {
+                    accelerator,
+                    shortcut,
+                };
+                state.lock().insert(id, binding);
+            }
+            Err(error) => warn!(%id, %error, "无法注册快捷键"),
+        }
+    }
+}
+
+#[cfg(desktop)]
+fn toggle_backend(app: &AppHandle) {
+    let app = app.clone();
+    let timeout = Duration::from_millis(DEFAULT_KILL_GRACE_MS);
+    async_runtime::spawn(async move {
+        let workspace = DEFAULT_WORKSPACE.to_string();
+        let result = if running_backend(&app, &workspace).is_some() {
+            run_blocking(move || shutdown_backend(&app, &workspace, timeout).map(drop)).await
+        } else {
+            tray::start_backend(app, timeout).await
+        };
+        if let Err(error) = result {
+            warn!(%error, "通过快捷键切换后端失败");
+        }
+    });
+}
+
+/// 全局快捷键插件的回调，只响应按下；不需要任何窗口处于聚焦状态
+#[cfg(desktop)]
+pub(crate) fn on_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let triggered = app
+        .state::<Shortcuts>()
+        .lock()
+        .iter()
+        .find(|(_, binding)| binding.shortcut == *shortcut)
+        .map(|(id, binding)| ShortcutTriggered {
+            id: id.clone(),
+            accelerator: binding.accelerator.clone(),
+        });
+    let Some(triggered) = triggered else {
+        return;
+    };
+    info!(id = %triggered.id, "触发快捷键");
+    match triggered.id.as_str() {
+        TOGGLE_MAIN_WINDOW => tray::toggle_main_window(app),
+        TOGGLE_BACKEND => toggle_backend(app),
+        _ => {}
+    }
+    let _ = app.emit(SHORTCUT_TRIGGERED_EVENT, triggered);
+}
+
+/// 把 `id` 绑定到 `accelerator`（如 `CommandOrControl+Shift+O`）并保存到设置；
+/// 已有绑定时先注销旧快捷键，新快捷键注册失败时恢复旧快捷键
+pub(crate) fn register(app: &AppHandle, id: String, accelerator: String) -> Result<(), PortError> {
+    let current = settings::load(app);
+    let mut updated = current.clone();
+    updated.shortcuts.insert(id, accelerator);
+    settings::replace(app, &current, &updated).map(drop)
+}
+
+/// 注销 `id` 的快捷键并从设置中删除，未绑定时直接返回
+pub(crate) fn unregister(app: &AppHandle, id: &str) -> Result<(), PortError> {
+    let current = settings::load(app);
+    let mut updated = current.clone();
+    if updated.shortcuts.remove(id).is_none() {
+        return Ok(());
+    }
+    settings::replace(app, &current, &updated).map(drop)
+}
+
+/// 设置中的全部快捷键，按 ID 排序
+pub(crate) fn list(app: &AppHandle) -> Vec<ShortcutBinding> {
+    #[cfg(desktop)]
+    let registered = app.state::<Shortcuts>().lock().clone();
+    let mut bindings: Vec<ShortcutBinding> = settings::load(app)
+        .shortcuts
+        .into_iter()
+        .map(|(id, accelerator)| ShortcutBinding {
+            #[cfg(desktop)]
+            registered: registered.contains_key(&id),
+            #[cfg(mobile)]
+            registered: false,
+            id,
+            accelerator,
+        })
+        .collect();
+    bindings.sort_by(|a, b| a.id.cmp(&b.id));
+    bindings
+}
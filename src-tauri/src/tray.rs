@@ -0,0 +1,230 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{TrayIcon, TrayIconBuilder};
+use tauri::{async_runtime, AppHandle, CloseRequestApi, Listener, Manager, Window};
+use tracing::warn;
+
+use crate::autostart::HIDDEN_ARG;
+use crate::backend::{
+    backend_status, restart_with_last_config, shutdown_backend, BackendPhase, BackendStatus,
+    BACKEND_EXITED_EVENT, BACKEND_STATUS_EVENT, DEFAULT_WORKSPACE,
+};
+use crate::ports::{can_bind, PortError};
+use crate::{run_blocking, settings, DEFAULT_KILL_GRACE_MS, MAIN_WINDOW};
+
+const TRAY_ID: &str = "main";
+const TOGGLE_ID: &str = "toggle-window";
+const STATUS_ID: &str = "backend-status";
+const START_ID: &str = "start-backend";
+const STOP_ID: &str = "stop-backend";
+const RESTART_ID: &str = "restart-backend";
+const QUIT_ID: &str = "quit";
+
+/// 托盘图标与需要随后端状态更新的菜单项；托盘不可用时不注册
+struct Tray {
+    status: MenuItem,
+    start: MenuItem,
+    stop: MenuItem,
+    _icon: TrayIcon,
+}
+
+/// 后端未运行时同时检查设置中的端口是否被其他进程占用，便于在启动前发现冲突
+fn status_text(app: &AppHandle, status: &BackendStatus) -> String {
+    let phase = match status.state {
+        BackendPhase::Stopped => "已停止",
+        BackendPhase::Starting => "启动中",
+        BackendPhase::Running => "运行中",
+        BackendPhase::Crashed => "已崩溃",
+        BackendPhase::Stopping => "正在停止",
+    };
+    match status.port {
+        Some(port) => format!("后端：{phase}（端口 {port}）"),
+        None => {
+            let port = settings::load(app).backend_port;
+            if can_bind(port) {
+                format!("后端：{phase}")
+            } else {
+                format!("后端：{phase}（端口 {port} 已被占用）")
+            }
+        }
+    }
+}
+
+/// 启动与停止只在对应状态下可用
+fn running(status: &BackendStatus) -> bool {
+    matches!(
+        status.state,
+        BackendPhase::Starting | BackendPhase::Running | BackendPhase::Stopping
+    )
+}
+
+fn build(app: &AppHandle) -> tauri::Result<Tray> {
+    let toggle = MenuItem::with_id(app, TOGGLE_ID, "显示/隐藏主窗口", true, None::<&str>)?;
+    let current = backend_status(app, DEFAULT_WORKSPACE);
+    let status_line = status_text(app, &current);
+    let status = MenuItem::with_id(app, STATUS_ID, status_line, false, None::<&str>)?;
+    let start = MenuItem::with_id(app, START_ID, "启动后端", !running(&current), None::<&str>)?;
+    let stop = MenuItem::with_id(app, STOP_ID, "停止后端", running(&current), None::<&str>)?;
+    let restart = MenuItem::with_id(app, RESTART_ID, "重启后端", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, QUIT_ID, "退出", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &toggle,
+            &PredefinedMenuItem::separator(app)?,
+            &status,
+            &start,
+            &stop,
+            &restart,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(&app.package_info().name)
+        .menu(&menu)
+        .on_menu_event(on_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    let icon = builder.build(app)?;
+    Ok(Tray {
+        status,
+        start,
+        stop,
+        _icon: icon,
+    })
+}
+
+/// 创建托盘图标，并在每次 `backend://status-changed` 与 `backend-exited` 时刷新状态行与启停菜单项；以 `--hidden` 自启动时隐藏主窗口。
+/// 托盘不可用时只记录警告，主窗口照常显示，关闭主窗口仍直接退出
+pub(crate) fn init(app: &AppHandle) {
+    // Linux 上缺少 appindicator 库时 tray-icon 直接 panic 而不是返回错误
+    let tray = match panic::catch_unwind(AssertUnwindSafe(|| build(app))) {
+        Ok(Ok(tray)) => tray,
+        Ok(Err(error)) => {
+            warn!(%error, "无法创建托盘图标");
+            return;
+        }
+        Err(_) => {
+            warn!("当前桌面环境不支持托盘图标");
+            return;
+        }
+    };
+    app.manage(tray);
+    if std::env::args().any(|arg| arg == HIDDEN_ARG) {
+        if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+            let _ = window.hide();
+        }
+    }
+    for event in [BACKEND_STATUS_EVENT, BACKEND_EXITED_EVENT] {
+        let handle = app.clone();
+        app.listen(event, move |_| refresh_status(&handle));
+    }
+}
+
+fn refresh_status(app: &AppHandle) {
+    let Some(tray) = app.try_state::<Tray>() else {
+        return;
+    };
+    let current = backend_status(app, DEFAULT_WORKSPACE);
+    let result = tray
+        .status
+        .set_text(status_text(app, &current))
+        .and_then(|_| tray.start.set_enabled(!running(&current)))
+        .and_then(|_| tray.stop.set_enabled(running(&current)));
+    if let Err(error) = result {
+        warn!(%error, "无法更新托盘中的后端状态");
+    }
+}
+
+/// 按上次的配置启动默认工作区的后端；从未启动过时使用设置中的端口
+pub(crate) async fn start_backend(app: AppHandle, timeout: Duration) -> Result<(), PortError> {
+    let workspace = DEFAULT_WORKSPACE.to_string();
+    match restart_with_last_config(app.clone(), workspace.clone(), None, timeout).await {
+        Err(PortError::BackendNotConfigured) => {
+            let port = settings::load(&app).backend_port;
+            restart_with_last_config(app, workspace, Some(port), timeout)
+                .await
+                .map(drop)
+        }
+        result => result.map(drop),
+    }
+}
+
+/// 显示并聚焦主窗口；窗口可能已隐藏到托盘或被最小化
+pub(crate) fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        // 先显示再取消最小化：窗口可能已隐藏到托盘
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+pub(crate) fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        show_main_window(app);
+    }
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        TOGGLE_ID => toggle_main_window(app),
+        START_ID => {
+            let app = app.clone();
+            let timeout = Duration::from_millis(DEFAULT_KILL_GRACE_MS);
+            async_runtime::spawn(async move {
+                if let Err(error) = start_backend(app, timeout).await {
+                    warn!(%error, "从托盘启动后端失败");
+                }
+            });
+        }
+        STOP_ID => {
+            let app = app.clone();
+            let timeout = Duration::from_millis(DEFAULT_KILL_GRACE_MS);
+            async_runtime::spawn(async move {
+                let stop = move || shutdown_backend(&app, DEFAULT_WORKSPACE, timeout);
+                if let Err(error) = run_blocking(stop).await {
+                    warn!(%error, "从托盘停止后端失败");
+                }
+            });
+        }
+        RESTART_ID => {
+            let app = app.clone();
+            let timeout = Duration::from_millis(DEFAULT_KILL_GRACE_MS);
+            async_runtime::spawn(async move {
+                let workspace = DEFAULT_WORKSPACE.to_string();
+                if let Err(error) = restart_with_last_config(app, workspace, None, timeout).await {
+                    warn!(%error, "从托盘重启后端失败");
+                }
+            });
+        }
+        // 与关闭最后一个窗口一样产生 `ExitRequested`，走同一套退出前停止后端的流程
+        QUIT_ID => app.exit(0),
+        _ => {}
+    }
+}
+
+/// 开启 `minimize_to_tray` 且托盘可用时，关闭主窗口只隐藏到托盘，应用与后端继续运行；
+/// 返回 `true` 表示已隐藏窗口并阻止了本次关闭
+pub(crate) fn hide_on_close(window: &Window, api: &CloseRequestApi) -> bool {
+    let app = window.app_handle();
+    if window.label() != MAIN_WINDOW || app.try_state::<Tray>().is_none() {
+        return false;
+    }
+    if !settings::load(app).minimize_to_tray {
+        return false;
+    }
+    api.prevent_close();
+    let _ = window.hide();
+    true
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+use crate::ports::{wait_for_state_cancellable, PortState};
+
+struct PendingWait {
+    port: u16,
+    token: CancellationToken,
+}
+
+#[derive(Default)]
+struct Waits {
+    next_id: u32,
+    entries: HashMap<u32, PendingWait>,
+}
+
+/// 正在进行的 `wait_for_port`，通过 `.manage()` 注册为全局状态。
+/// 每次等待使用独立的令牌，取消只影响当时正在进行的等待
+#[derive(Default)]
+pub(crate) struct PortWaits {
+    inner: Mutex<Waits>,
+}
+
+impl PortWaits {
+    fn lock(&self) -> MutexGuard<'_, Waits> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn register(&self, port: u16) -> (u32, CancellationToken) {
+        let mut waits = self.lock();
+        waits.next_id = waits.next_id.wrapping_add(1);
+        let id = waits.next_id;
+        let token = CancellationToken::new();
+        waits.entries.insert(
+            id,
+            PendingWait {
+                port,
+                token: token.clone(),
+            },
+        );
+        (id, token)
+    }
+
+    /// 取消等待 `port` 的全部请求，`port` 为 `None` 时取消所有等待；返回被取消的数量
+    pub(crate) fn cancel(&self, port: Option<u16>) -> usize {
+        let mut waits = self.lock();
+        let ids: Vec<u32> = waits
+            .entries
+            .iter()
+            .filter(|(_, wait)| port.is_none_or(|port| wait.port == port))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &ids {
+            if let Some(wait) = waits.entries.remove(id) {
+                wait.token.cancel();
+            }
+        }
+        ids.len()
+    }
+
+    /// 登记一次可被 `cancel` 中断的等待，结束后注销
+    async fn wait(
+        &self,
+        port: u16,
+        hosts: Option<&[IpAddr]>,
+        state: PortState,
+        timeout: Duration,
+        interval: Duration,
+    ) -> bool {
+        let (id, token) = self.register(port);
+        let reached =
+            wait_for_state_cancellable(port, hosts, state, timeout, interval, &token).await;
+        self.lock().entries.remove(&id);
+        reached
+    }
+}
+
+/// 可被 `cancel_wait` 中断的端口等待，被取消时与超时一样返回 `false`；`hosts` 见 `wait_for_state_cancellable`
+pub(crate) async fn wait_for_port_state(
+    app: &AppHandle,
+    port: u16,
+    hosts: Option<&[IpAddr]>,
+    state: PortState,
+    timeout: Duration,
+    interval: Duration,
+) -> bool {
+    app.state::<PortWaits>()
+        .wait(port, hosts, state, timeout, interval)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Instant;
+
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(20);
+
+    fn free_port() -> u16 {
+        TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    fn wait(waits: &PortWaits, port: u16, state: PortState, timeout: Duration) -> bool {
+        tauri::async_runtime::block_on(waits.wait(port, None, state, timeout, INTERVAL))
+    }
+
+    #[test]
+    fn bound_listener_is_open_immediately() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let waits = PortWaits::default();
+        let started = Instant::now();
+        assert!(wait(&waits, port, PortState::Open, Duration::from_secs(10)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(waits.lock().entries.is_empty());
+    }
+
+    #[test]
+    fn free_port_times_out_waiting_to_open() {
+        let waits = PortWaits::default();
+        let timeout = Duration::from_millis(200);
+        let started = Instant::now();
+        assert!(!wait(&waits, free_port(), PortState::Open, timeout));
+        assert!(started.elapsed() >= timeout);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(waits.lock().entries.is_empty());
+    }
+
+    #[test]
+    fn cancel_interrupts_a_pending_wait() {
+        let waits = Arc::new(PortWaits::default());
+        let port = free_port();
+        let canceller = thread::spawn({
+            let waits = Arc::clone(&waits);
+            move || {
+                // 等到等待已登记并进入轮询后再取消
+                while waits.lock().entries.is_empty() {
+                    thread::sleep(INTERVAL);
+                }
+                thread::sleep(INTERVAL * 3);
+                (
+                    waits.cancel(Some(port.wrapping_add(1))),
+                    waits.cancel(Some(port)),
+                )
+            }
+        });
+        let started = Instant::now();
+        assert!(!wait(
+            &waits,
+            port,
+            PortState::Open,
+            Duration::from_secs(30)
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(canceller.join().unwrap(), (0, 1));
+        assert!(waits.lock().entries.is_empty());
+        assert_eq!(waits.cancel(None), 0);
+    }
+}
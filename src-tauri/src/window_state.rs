@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    async_runtime, AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow,
+    Window, WindowEvent,
+};
+use tauri_plugin_store::StoreExt;
+use tracing::warn;
+
+use crate::ports::PortError;
+use crate::store_failed;
+
+/// 窗口位置单独存放，频繁写入不影响设置文件，也不会出现在设置的导出与备份中
+const WINDOW_STATE_STORE: &str = "window-state.json";
+/// 移动或缩放停止后再写入，期间的事件合并为一次写入
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+/// 保存的窗口在某个显示器内至少露出这么大的区域（边长，物理像素）才按原位置恢复，否则居中
+const MIN_VISIBLE: i64 = 100;
+
+/// 窗口的位置（外框左上角）与内容区大小，物理像素；最大化或全屏时保留之前的普通大小
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    maximized: bool,
+    fullscreen: bool,
+}
+
+/// 各窗口最近的位置与大小，以窗口 label 为键，通过 `.manage()` 注册为全局状态
+#[derive(Default)]
+pub(crate) struct WindowStates {
+    geometry: Mutex<HashMap<String, WindowGeometry>>,
+    /// 已安排延迟写入，期间的变化并入同一次写入
+    save_scheduled: AtomicBool,
+}
+
+impl WindowStates {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, WindowGeometry>> {
+        self.geometry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// 读取窗口当前状态；最小化时位置没有意义（Windows 上为 -32000），返回 `None`
+fn capture(window: &Window, previous: Option<WindowGeometry>) -> Option<WindowGeometry> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let fullscreen = window.is_fullscreen().unwrap_or(false);
+    if maximized || fullscreen {
+        let previous = previous?;
+        return Some(WindowGeometry {
+            maximized,
+            fullscreen,
+            ..previous
+        });
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized,
+        fullscreen,
+    })
+}
+
+/// 窗口移动、缩放时更新内存中的状态并安排延迟写入；窗口销毁时立即写入
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    let app = window.app_handle();
+    match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            let states = app.state::<WindowStates>();
+            let previous = states.lock().get(window.label()).copied();
+            let Some(current) = capture(window, previous) else {
+                return;
+            };
+            if previous == Some(current) {
+                return;
+            }
+            states.lock().insert(window.label().to_string(), current);
+            schedule_save(app);
+        }
+        WindowEvent::Destroyed => save(app),
+        _ => {}
+    }
+}
+
+fn schedule_save(app: &AppHandle) {
+    if app
+        .state::<WindowStates>()
+        .save_scheduled
+        .swap(true, Ordering::SeqCst)
+    {
+        return;
+    }
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+        save(&app);
+    });
+}
+
+/// 写入存储的各项，以窗口 label 为键
+fn encode_entries(geometry: HashMap<String, WindowGeometry>) -> Vec<(String, serde_json::Value)> {
+    geometry
+        .into_iter()
+        .filter_map(|(label, state)| Some((label, serde_json::to_value(state).ok()?)))
+        .collect()
+}
+
+/// 从存储读出的各项，格式不符的项被忽略
+fn decode_entries(
+    entries: impl IntoIterator<Item = (String, serde_json::Value)>,
+) -> Vec<(String, WindowGeometry)> {
+    entries
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, serde_json::from_value(value).ok()?)))
+        .collect()
+}
+
+/// 把内存中所有窗口的状态写入存储
+fn save(app: &AppHandle) {
+    let states = app.state::<WindowStates>();
+    states.save_scheduled.store(false, Ordering::SeqCst);
+    let geometry = states.lock().clone();
+    let result = app.store(WINDOW_STATE_STORE).and_then(|store| {
+        for (label, value) in encode_entries(geometry) {
+            store.set(label, value);
+        }
+        store.save()
+    });
+    if let Err(error) = result {
+        warn!(%error, "无法保存窗口位置");
+    }
+}
+
+/// 保存的窗口与显示器重叠部分的宽与高
+fn overlap(state: &WindowGeometry, monitor: &Monitor) -> (i64, i64) {
+    let (left, top) = (
+        i64::from(monitor.position().x),
+        i64::from(monitor.position().y),
+    );
+    let right = left + i64::from(monitor.size().width);
+    let bottom = top + i64::from(monitor.size().height);
+    let (x, y) = (i64::from(state.x), i64::from(state.y));
+    let width = (x + i64::from(state.width)).min(right) - x.max(left);
+    let height = (y + i64::from(state.height)).min(bottom) - y.max(top);
+    (width.max(0), height.max(0))
+}
+
+/// 把窗口恢复到保存的状态；保存时所在的显示器已断开（或窗口基本不可见）时改为在当前显示器居中，
+/// 大小也不超过显示器
+fn restore(window: &WebviewWindow, state: WindowGeometry) {
+    let monitors = window.available_monitors().unwrap_or_default();
+    let visible = monitors
+        .iter()
+        .map(|monitor| (monitor, overlap(&state, monitor)))
+        .filter(|(_, (width, height))| *width >= MIN_VISIBLE && *height >= MIN_VISIBLE)
+        .max_by_key(|(_, (width, height))| width * height)
+        .map(|(monitor, _)| monitor);
+
+    let mut size = PhysicalSize {
+        width: state.width,
+        height: state.height,
+    };
+    if let Some(monitor) = visible.or(monitors.first()) {
+        size.width = size.width.min(monitor.size().width);
+        size.height = size.height.min(monitor.size().height);
+    }
+    let _ = window.set_size(size);
+    match visible {
+        Some(monitor) => {
+            // 露出的部分足够时仍把窗口整体挪回该显示器内
+            let right = monitor.position().x + monitor.size().width as i32 - size.width as i32;
+            let bottom = monitor.position().y + monitor.size().height as i32 - size.height as i32;
+            let position = PhysicalPosition {
+                x: state
+                    .x
+                    .clamp(monitor.position().x, right.max(monitor.position().x)),
+                y: state
+                    .y
+                    .clamp(monitor.position().y, bottom.max(monitor.position().y)),
+            };
+            let _ = window.set_position(position);
+        }
+        None if monitors.is_empty() => {
+            let _ = window.set_position(PhysicalPosition {
+                x: state.x,
+                y: state.y,
+            });
+        }
+        None => {
+            let _ = window.center();
+        }
+    }
+    if state.maximized {
+        let _ = window.maximize();
+    }
+    if state.fullscreen {
+        let _ = window.set_fullscreen(true);
+    }
+}
+
+/// 按启动时读入内存的状态恢复之后新建的窗口，没有保存的状态时不做处理
+pub(crate) fn restore_saved(window: &WebviewWindow) {
+    let state = window
+        .app_handle()
+        .state::<WindowStates>()
+        .lock()
+        .get(window.label())
+        .copied();
+    if let Some(state) = state {
+        restore(window, state);
+    }
+}
+
+/// 启动时读取保存的窗口状态并恢复已创建的窗口
+pub(crate) fn restore_all(app: &AppHandle) {
+    let store = match app.store(WINDOW_STATE_STORE) {
+        Ok(store) => store,
+        Err(error) => {
+            warn!(%error, "无法读取窗口位置");
+            return;
+        }
+    };
+    let saved = decode_entries(store.entries());
+    app.state::<WindowStates>()
+        .lock()
+        .extend(saved.iter().cloned());
+    // 恢复时产生的移动、缩放事件会同步回调，不能持有锁
+    for (label, state) in saved {
+        if let Some(window) = app.get_webview_window(&label) {
+            restore(&window, state);
+        }
+    }
+}
+
+/// 删除 `label` 窗口保存的状态；窗口已打开时退出最大化与全屏并居中，之后的移动与缩放照常记录
+pub(crate) fn reset(app: &AppHandle, label: &str) -> Result<(), PortError> {
+    app.state::<WindowStates>().lock().remove(label);
+    let store = app.store(WINDOW_STATE_STORE).map_err(store_failed)?;
+    store.delete(label);
+    store.save().map_err(store_failed)?;
+    if let Some(window) = app.get_webview_window(label) {
+        let _ = window.set_fullscreen(false);
+        let _ = window.unmaximize();
+        let _ = window.center();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_state_reads_back_unchanged() {
+        let main = WindowGeometry {
+            x: -1280,
+            y: 40,
+            width: 1200,
+            height: 800,
+            maximized: true,
+            fullscreen: false,
+        };
+        let settings = WindowGeometry {
+            x: 100,
+            y: 100,
+            width: 640,
+            height: 480,
+            maximized: false,
+            fullscreen: true,
+        };
+        let geometry = HashMap::from([
+            ("main".to_string(), main),
+            ("settings".to_string(), settings),
+        ]);
+
+        // 与存储文件一样经过一次 JSON 文本
+        let file: serde_json::Map<String, serde_json::Value> =
+            encode_entries(geometry.clone()).into_iter().collect();
+        let content = serde_json::to_string(&file).unwrap();
+        let file: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&content).unwrap();
+        let restored: HashMap<String, WindowGeometry> = decode_entries(file).into_iter().collect();
+        assert_eq!(restored, geometry);
+    }
+
+    #[test]
+    fn malformed_entries_are_skipped() {
+        let entries = vec![
+            ("main".to_string(), serde_json::json!({ "x": 1, "y": 2 })),
+            ("other".to_string(), serde_json::json!("not a window")),
+        ];
+        assert!(decode_entries(entries).is_empty());
+    }
+}
@@ -3,7 +3,7 @@
  * 使用 Tauri Store 存储应用配置
  */
 
-import { isTauri } from "@tauri-apps/api/core";
+import { invoke, isTauri } from "@tauri-apps/api/core";
 import { LazyStore } from "@tauri-apps/plugin-store";
 import type {
   AppConfig,
@@ -16,7 +16,8 @@ import { PRESET_PROVIDERS } from "../constants/preset-providers";
 export type ThemePreference = "dark" | "light" | "system";
 
 interface StoredSecret {
-  mode: "plain" | "enc";
+  /** keychain 时 value 为系统钥匙串中的键名 */
+  mode: "plain" | "enc" | "keychain";
   value: string;
   iv?: string;
 }
@@ -72,6 +73,9 @@ const STORE_THEME_KEY = "theme";
 // 可选加密开关：开启后优先尝试加密，失败会自动降级为明文存储
 const ENABLE_OPTIONAL_SECRET_ENCRYPTION = true;
 const SECRET_KEY_NS = "com.nooldey.code-reviewer.v3";
+// 系统钥匙串中的键名，与 Rust 侧启动迁移使用的键名一致
+const GITLAB_TOKEN_SECRET = "gitlab_token";
+const PROVIDER_KEY_SECRET_PREFIX = "provider_api_key.";
 
 let storeInstance: LazyStore | null = null;
 
@@ -208,8 +212,29 @@ async function deriveSecretKey(): Promise<CryptoKey | null> {
   );
 }
 
-async function encodeSecret(value: string): Promise<StoredSecret | undefined> {
-  if (!value) return undefined;
+/** 本次运行中读取失败的钥匙串键名；界面上显示为空，保存时保留原有取值而不是删除 */
+const unreadableKeychainKeys = new Set<string>();
+
+async function encodeSecret(
+  value: string,
+  keychainKey: string
+): Promise<StoredSecret | undefined> {
+  if (!value && unreadableKeychainKeys.has(keychainKey)) {
+    return { mode: "keychain", value: keychainKey };
+  }
+  if (!value) {
+    await invoke("delete_secret", { key: keychainKey }).catch(() => undefined);
+    return undefined;
+  }
+
+  // 优先保存到系统钥匙串，失败时降级为下面的加密或明文存储
+  try {
+    await invoke("set_secret", { key: keychainKey, value });
+    unreadableKeychainKeys.delete(keychainKey);
+    return { mode: "keychain", value: keychainKey };
+  } catch (e) {
+    console.error("Keychain unavailable, fallback to store:", e);
+  }
 
   try {
     const key = await deriveSecretKey();
@@ -237,6 +262,17 @@ async function encodeSecret(value: string): Promise<StoredSecret | undefined> {
 async function decodeSecret(secret?: StoredSecret): Promise<string> {
   if (!secret?.value) return "";
   if (secret.mode === "plain") return secret.value;
+  if (secret.mode === "keychain") {
+    try {
+      const value = (await invoke<string | null>("get_secret", { key: secret.value })) ?? "";
+      unreadableKeychainKeys.delete(secret.value);
+      return value;
+    } catch (e) {
+      console.error("Keychain read failed:", e);
+      unreadableKeychainKeys.add(secret.value);
+      return "";
+    }
+  }
 
   try {
     const key = await deriveSecretKey();
@@ -262,13 +298,14 @@ async function toStoreConfig(config: AppConfig, theme?: ThemePreference): Promis
   }));
 
   for (const provider of config.ai.modeProviders) {
-    if (provider.apiKey) {
-      const encodedProviderKey = await encodeSecret(provider.apiKey);
-      if (encodedProviderKey) {
-        providerApiKeys[provider.id] = encodedProviderKey;
-      }
+    // 空值同样交给 encodeSecret：读取失败的钥匙串键保留原有引用，其余清除旧的取值
+    const encodedProviderKey = await encodeSecret(
+      provider.apiKey ?? "",
+      `${PROVIDER_KEY_SECRET_PREFIX}${provider.id}`
+    );
+    if (encodedProviderKey) {
+      providerApiKeys[provider.id] = encodedProviderKey;
     }
-
   }
 
   const now = new Date().toISOString();
@@ -285,7 +322,7 @@ async function toStoreConfig(config: AppConfig, theme?: ThemePreference): Promis
     },
     secrets: {
       providerApiKeys,
-      gitlabToken: await encodeSecret(config.gitlab.token),
+      gitlabToken: await encodeSecret(config.gitlab.token, GITLAB_TOKEN_SECRET),
     },
     sync: {
       enabled: false,
@@ -402,6 +439,19 @@ export async function loadConfig(): Promise<AppConfig> {
   }
 }
 
+/** 删除已被移除的供应商保存在系统钥匙串中的 API Key */
+async function deleteRemovedProviderSecrets(
+  previous: Record<string, StoredSecret> | undefined,
+  config: AppConfig
+): Promise<void> {
+  const remaining = new Set(config.ai.modeProviders.map((provider) => provider.id));
+  for (const [providerId, secret] of Object.entries(previous ?? {})) {
+    if (remaining.has(providerId) || secret.mode !== "keychain") continue;
+    await invoke("delete_secret", { key: secret.value }).catch(() => undefined);
+    unreadableKeychainKeys.delete(secret.value);
+  }
+}
+
 /** 保存完整配置 */
 export async function saveConfig(config: AppConfig): Promise<void> {
   if (!isTauri()) {
@@ -410,10 +460,14 @@ export async function saveConfig(config: AppConfig): Promise<void> {
   }
 
   const theme = await loadThemePreference();
-  const storeConfig = await toStoreConfig(config, theme);
   const store = getStore();
+  const previous = await store.get<AppConfigStoreV3>(STORE_CONFIG_KEY);
+  const previousProviderKeys =
+    previous?.schemaVersion === 3 ? previous.secrets?.providerApiKeys : undefined;
+  const storeConfig = await toStoreConfig(config, theme);
   await store.set(STORE_CONFIG_KEY, storeConfig);
   await store.save();
+  await deleteRemovedProviderSecrets(previousProviderKeys, config);
 }
 
 /** 获取 GitLab 配置 */